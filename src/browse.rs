@@ -0,0 +1,225 @@
+use ansi_term::Colour;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    DefaultTerminal, Frame,
+};
+
+use crate::color;
+use crate::model::{matches_keyword, DifficultyType, ProblemInfo};
+use crate::Result;
+
+/// Rows skipped by PageUp and PageDown.
+const PAGE_SIZE: usize = 20;
+
+/// Problems shown by `browse`, narrowed down as the user types.
+struct Browser<'a> {
+    probs: &'a [Box<dyn ProblemInfo + Send>],
+    filter: String,
+
+    /// Indexes in `probs` of the problems matching `filter`.
+    visible: Vec<usize>,
+    state: TableState,
+}
+
+impl<'a> Browser<'a> {
+    fn new(probs: &'a [Box<dyn ProblemInfo + Send>]) -> Self {
+        let mut browser = Browser {
+            probs,
+            filter: String::new(),
+            visible: vec![],
+            state: TableState::default(),
+        };
+        browser.apply_filter();
+        browser
+    }
+
+    /// Keep the problems matching the filter, selecting the first one.
+    fn apply_filter(&mut self) {
+        self.visible = self
+            .probs
+            .iter()
+            .enumerate()
+            .filter(|(_, prob)| matches_keyword(&self.filter, prob.as_ref()))
+            .map(|(i, _)| i)
+            .collect();
+        let selected = if self.visible.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.state.select(selected);
+    }
+
+    /// Move the selection by `delta` rows, stopping at either end.
+    fn move_by(&mut self, delta: isize) {
+        if let Some(selected) = self.state.selected() {
+            let last = self.visible.len().saturating_sub(1);
+            let selected = selected.saturating_add_signed(delta).min(last);
+            self.state.select(Some(selected));
+        }
+    }
+
+    fn selected(&self) -> Option<&(dyn ProblemInfo + Send)> {
+        self.state
+            .selected()
+            .and_then(|i| self.visible.get(i))
+            .map(|&i| self.probs[i].as_ref())
+    }
+
+    /// Handle a key press, returning whether browsing is over.
+    fn on_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        match code {
+            KeyCode::Esc => return true,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return true,
+            KeyCode::Enter => return self.selected().is_some(),
+            KeyCode::Up => self.move_by(-1),
+            KeyCode::Down => self.move_by(1),
+            KeyCode::PageUp => self.move_by(-(PAGE_SIZE as isize)),
+            KeyCode::PageDown => self.move_by(PAGE_SIZE as isize),
+            KeyCode::Home => self.move_by(isize::MIN),
+            KeyCode::End => self.move_by(isize::MAX),
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.apply_filter();
+            }
+            KeyCode::Char(c) => {
+                self.filter.push(c);
+                self.apply_filter();
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [list_area, filter_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(3)]).areas(frame.area());
+
+        let rows = self.visible.iter().map(|&i| {
+            let prob = self.probs[i].as_ref();
+            let difficulty = DifficultyType::from(prob.difficulty());
+            let colour = match difficulty {
+                DifficultyType::Unknown => Color::Reset,
+                level => tui_colour(level.color()),
+            };
+            Row::new(vec![
+                Cell::from(if prob.status().is_some() { "✔" } else { "" }),
+                Cell::from(if prob.is_paid_only() { "🔒" } else { "" }),
+                Cell::from(prob.question_id().to_string()),
+                Cell::from(prob.question_title().to_string()),
+                Cell::from(
+                    prob.acceptance()
+                        .map(|acceptance| format!("{:.1}%", acceptance * 100.0))
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                Cell::from(difficulty.to_string()).style(Style::default().fg(colour)),
+            ])
+        });
+        let widths = [
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Length(5),
+            Constraint::Min(20),
+            Constraint::Length(6),
+            Constraint::Length(6),
+        ];
+        let title = format!(
+            " {}/{} problems, Enter to pick, Esc to quit ",
+            self.visible.len(),
+            self.probs.len()
+        );
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec!["", "", "ID", "Title", "AC", "Level"])
+                    .style(Style::default().add_modifier(Modifier::BOLD)),
+            )
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, list_area, &mut self.state);
+
+        let filter = Paragraph::new(Line::from(self.filter.as_str()))
+            .block(Block::default().borders(Borders::ALL).title(" Filter "));
+        frame.render_widget(filter, filter_area);
+    }
+}
+
+/// Terminal color of an `ansi_term` one, plain with `--no-color`.
+fn tui_colour(colour: Colour) -> Color {
+    if !color::is_enabled() {
+        return Color::Reset;
+    }
+    match colour {
+        Colour::Black => Color::Black,
+        Colour::Red => Color::Red,
+        Colour::Green => Color::Green,
+        Colour::Yellow => Color::Yellow,
+        Colour::Blue => Color::Blue,
+        Colour::Purple => Color::Magenta,
+        Colour::Cyan => Color::Cyan,
+        Colour::White => Color::Gray,
+        Colour::Fixed(index) => Color::Indexed(index),
+        Colour::RGB(r, g, b) => Color::Rgb(r, g, b),
+    }
+}
+
+/// Browse `probs` in a full screen table, returning the ID of the problem
+/// picked with Enter, or `None` if the user quit.
+pub fn browse(probs: &[Box<dyn ProblemInfo + Send>]) -> Result<Option<usize>> {
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, Browser::new(probs));
+    ratatui::restore();
+
+    result
+}
+
+fn run(terminal: &mut DefaultTerminal, mut browser: Browser) -> Result<Option<usize>> {
+    loop {
+        terminal.draw(|frame| browser.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press || !browser.on_key(key.code, key.modifiers) {
+                continue;
+            }
+            if key.code == KeyCode::Enter {
+                return Ok(browser.selected().map(|prob| prob.question_id()));
+            }
+            return Ok(None);
+        }
+    }
+}
+
+#[test]
+fn test_browser() {
+    use crate::model::stat_pair;
+
+    let probs: Vec<Box<dyn ProblemInfo + Send>> = ["Two Sum", "Add Two Numbers", "3Sum"]
+        .iter()
+        .enumerate()
+        .map(|(i, title)| Box::new(stat_pair(i + 1, title, 1, 2)) as Box<dyn ProblemInfo + Send>)
+        .collect();
+    let mut browser = Browser::new(&probs);
+    assert_eq!(browser.visible, [0, 1, 2]);
+
+    browser.on_key(KeyCode::End, KeyModifiers::NONE);
+    assert_eq!(browser.selected().unwrap().question_id(), 3);
+    browser.on_key(KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(browser.selected().unwrap().question_id(), 3);
+
+    for c in "two".chars() {
+        browser.on_key(KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    assert_eq!(browser.visible, [0, 1]);
+    browser.on_key(KeyCode::Down, KeyModifiers::NONE);
+    assert!(browser.on_key(KeyCode::Enter, KeyModifiers::NONE));
+    assert_eq!(browser.selected().unwrap().question_id(), 2);
+
+    browser.on_key(KeyCode::Char('x'), KeyModifiers::NONE);
+    assert!(browser.selected().is_none());
+    assert!(!browser.on_key(KeyCode::Enter, KeyModifiers::NONE));
+    browser.on_key(KeyCode::Backspace, KeyModifiers::NONE);
+    assert_eq!(browser.visible, [0, 1]);
+    assert!(browser.on_key(KeyCode::Esc, KeyModifiers::NONE));
+}
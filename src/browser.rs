@@ -0,0 +1,32 @@
+use std::process::{Command, Stdio};
+
+use anyhow::anyhow;
+
+use crate::{LeetUpError, Result};
+
+/// Open `url` with the platform's default browser.
+pub fn open(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let status = command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(LeetUpError::Any(anyhow!(
+            "Failed to open {} in a browser",
+            url
+        )))
+    }
+}
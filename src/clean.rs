@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use leetup_cache::kvstore::KvStore;
+
+use crate::{service::CacheKey, snapshot::SNAPSHOT_DIR, Result};
+
+/// Files of the leetup directory `clean` would delete.
+///
+/// Only the cache logs and problem list snapshots are picked, plus the
+/// `config` file when `all` is set. Anything else, e.g. the submission
+/// history, is left alone.
+pub fn files_to_clean(dir: &Path, config: &Path, all: bool) -> Result<Vec<(PathBuf, u64)>> {
+    let mut files = vec![];
+    if all && config.is_file() {
+        files.push((config.to_path_buf(), config.metadata()?.len()));
+    }
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let is_log = path.extension().is_some_and(|ext| ext == "log")
+            && path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| stem.parse::<u64>().is_ok());
+        if is_log {
+            files.push((path, metadata.len()));
+        }
+    }
+    snapshot_files(&dir.join(SNAPSHOT_DIR), &mut files)?;
+    files.sort();
+
+    Ok(files)
+}
+
+/// JSON files under the snapshot directory `dir`, one per site and user.
+fn snapshot_files(dir: &Path, files: &mut Vec<(PathBuf, u64)>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let path = entry.path();
+        if metadata.is_dir() {
+            snapshot_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            files.push((path, metadata.len()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove cached data from the leetup directory `dir`.
+///
+/// The session is kept unless `all` is set, which also removes the
+/// `config` file in use. With `dry_run` the files are only listed.
+pub fn clean(dir: &Path, config: &Path, all: bool, dry_run: bool) -> Result<()> {
+    let files = files_to_clean(dir, config, all)?;
+    let size: u64 = files.iter().map(|(_, size)| size).sum();
+    if dry_run {
+        for (path, size) in &files {
+            println!("{} ({})", path.display(), human_size(*size));
+        }
+        println!("Would free {}", human_size(size));
+        return Ok(());
+    }
+
+    let session = if all || files.is_empty() {
+        None
+    } else {
+        KvStore::open(dir)?.get(CacheKey::Session.into())?
+    };
+    // Opening the store above created a new log, pick it up as well.
+    for (path, _) in files_to_clean(dir, config, all)? {
+        fs::remove_file(path)?;
+    }
+    // Only the emptied site directories are left in there.
+    let snapshot_dir = dir.join(SNAPSHOT_DIR);
+    if snapshot_dir.is_dir() {
+        fs::remove_dir_all(snapshot_dir)?;
+    }
+    if let Some(session) = session {
+        KvStore::open(dir)?.set(CacheKey::Session.into(), session)?;
+    }
+    println!("Freed {}", human_size(size));
+
+    Ok(())
+}
+
+/// Size in bytes as B, KiB or MiB.
+pub fn human_size(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    let bytes_f = bytes as f64;
+    if bytes_f < KIB {
+        format!("{} B", bytes)
+    } else if bytes_f < KIB * KIB {
+        format!("{:.1} KiB", bytes_f / KIB)
+    } else {
+        format!("{:.1} MiB", bytes_f / KIB / KIB)
+    }
+}
+
+#[test]
+fn test_human_size() {
+    assert_eq!(human_size(0), "0 B");
+    assert_eq!(human_size(1023), "1023 B");
+    assert_eq!(human_size(1536), "1.5 KiB");
+    assert_eq!(human_size(3 * 1024 * 1024), "3.0 MiB");
+}
+
+#[test]
+fn test_clean() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = KvStore::open(dir.path()).unwrap();
+    store
+        .set(CacheKey::Session.into(), "session".into())
+        .unwrap();
+    store.set("problems".into(), "x".repeat(100)).unwrap();
+    drop(store);
+    let config = dir.path().join("config.json");
+    fs::write(&config, "{}").unwrap();
+    let config_dir = tempfile::tempdir().unwrap();
+    let toml = config_dir.path().join("config.toml");
+    fs::write(&toml, "").unwrap();
+    fs::write(dir.path().join("history.jsonl"), "").unwrap();
+    fs::write(dir.path().join("notes.log"), "").unwrap();
+    let snapshot = dir.path().join(SNAPSHOT_DIR).join("leetcode.com");
+    fs::create_dir_all(&snapshot).unwrap();
+    fs::write(snapshot.join("anonymous.json"), "{}").unwrap();
+
+    let names = |all| -> Vec<String> {
+        files_to_clean(dir.path(), &config, all)
+            .unwrap()
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().into())
+            .collect()
+    };
+    assert_eq!(names(false), ["1.log", "anonymous.json"]);
+    assert_eq!(names(true), ["1.log", "config.json", "anonymous.json"]);
+    // Only the config in use is removed
+    assert_eq!(files_to_clean(dir.path(), &toml, true).unwrap().len(), 3);
+    assert!(files_to_clean(dir.path(), &toml, true)
+        .unwrap()
+        .iter()
+        .any(|(path, _)| path == &toml));
+
+    clean(dir.path(), &config, false, true).unwrap();
+    assert_eq!(names(false), ["1.log", "anonymous.json"]);
+
+    clean(dir.path(), &config, false, false).unwrap();
+    let mut store = KvStore::open(dir.path()).unwrap();
+    assert_eq!(
+        store.get(CacheKey::Session.into()).unwrap(),
+        Some("session".into())
+    );
+    assert_eq!(store.get("problems".into()).unwrap(), None);
+    drop(store);
+    assert!(!dir.path().join(SNAPSHOT_DIR).exists());
+
+    clean(dir.path(), &config, true, false).unwrap();
+    assert!(names(true).is_empty());
+    assert!(dir.path().join("history.jsonl").exists());
+    assert!(dir.path().join("notes.log").exists());
+}
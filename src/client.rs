@@ -1,7 +1,29 @@
+use std::time::{Duration, Instant};
+
 use crate::{service::Session, Config, LeetUpError, Result};
-use anyhow::anyhow;
-use log::debug;
-use reqwest::{header, header::HeaderMap, header::HeaderValue, Client, Response};
+use log::{debug, info, warn};
+use reqwest::{
+    header, header::HeaderMap, header::HeaderValue, Client, RequestBuilder, Response, StatusCode,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+/// Delay before the first retry, doubled on every following attempt.
+const RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Wait used for a 429 response without a `Retry-After` header.
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(10);
+
+/// Body of a conditional GET, see `RemoteClient::get_if_changed`.
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    /// The server answered 304, the copy matching the ETag is current.
+    NotModified,
+    Modified {
+        body: String,
+        etag: Option<String>,
+    },
+}
 
 pub struct RemoteClient<'a> {
     config: &'a Config,
@@ -20,9 +42,64 @@ impl<'a> RemoteClient<'_> {
         headers_opt: Option<HeaderMap>,
         session: Option<&Session>,
     ) -> Result<Response> {
-        let headers = self.headers_with_session(headers_opt, session);
-        let client = Client::builder().default_headers(headers).build()?;
-        client.get(url).send().await.map_err(LeetUpError::Reqwest)
+        let headers = self.headers_with_session(headers_opt, session)?;
+        let client = self.client(headers)?;
+        self.send_with_retry(session.is_some(), || client.get(url))
+            .await
+    }
+
+    /// Make a GET request and deserialize the JSON response.
+    ///
+    /// Retries and status checks are the same as `get`, a body that doesn't
+    /// match `T` is a `LeetUpError::Serde`.
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        session: Option<&Session>,
+    ) -> Result<T> {
+        let body = self.get(url, None, session).await?.bytes().await?;
+        serde_json::from_slice(&body).map_err(|e| {
+            warn!("Unexpected response from {}: {}", url, e);
+            e.into()
+        })
+    }
+
+    /// Make a GET request with `If-None-Match: etag`, when given.
+    ///
+    /// Servers without ETag support simply answer with the full body.
+    pub async fn get_if_changed(
+        &self,
+        url: &str,
+        session: Option<&Session>,
+        etag: Option<&str>,
+    ) -> Result<Fetched> {
+        let headers = etag
+            .and_then(|etag| HeaderValue::from_str(etag).ok())
+            .map(|etag| {
+                let mut headers = HeaderMap::new();
+                headers.insert(header::IF_NONE_MATCH, etag);
+                headers
+            });
+        let res = self.get(url, headers, session).await?;
+        if res.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Fetched::NotModified);
+        }
+        let etag = res
+            .headers()
+            .get(header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(String::from);
+        let body = res.text().await?;
+
+        Ok(Fetched::Modified { body, etag })
+    }
+
+    /// Make a DELETE request
+    pub async fn delete(&self, url: &str, headers_opt: Option<HeaderMap>) -> Result<Response> {
+        let headers = self.headers_with_session(headers_opt, self.session)?;
+        let client = self.client(headers)?;
+        self.send_with_retry(self.session.is_some(), || client.delete(url))
+            .await
     }
 
     /// Make a POST request
@@ -35,26 +112,135 @@ impl<'a> RemoteClient<'_> {
     where
         F: FnOnce() -> Option<HeaderMap>,
     {
-        let headers = self.headers_with_session(with_headers(), self.session);
+        let headers = self.headers_with_session(with_headers(), self.session)?;
         debug!("Headers: {:#?}", headers);
-        let client = Client::builder().default_headers(headers).build()?;
+        let client = self.client(headers)?;
+        let origin = HeaderValue::from_str(&self.config.urls.base)?;
 
-        let client = client
-            .post(url)
-            .header(
-                header::ORIGIN,
-                HeaderValue::from_str(&self.config.urls.base).unwrap(),
-            )
-            .json(body);
+        let res = self
+            .send_with_retry(self.session.is_some(), || {
+                client
+                    .post(url)
+                    .header(header::ORIGIN, origin.clone())
+                    .json(body)
+            })
+            .await?;
 
-        let res = client.send().await?;
+        res.json::<serde_json::value::Value>()
+            .await
+            .map_err(|e| e.into())
+    }
 
-        if res.status() == 200 {
-            res.json::<serde_json::value::Value>()
-                .await
-                .map_err(|e| e.into())
-        } else {
-            Err(LeetUpError::Any(anyhow!("Status: {}", res.status())))
+    /// Run a GraphQL query and deserialize its `data`.
+    ///
+    /// Errors reported by the server are turned into `LeetUpError::GraphQL`.
+    pub async fn graphql<T: DeserializeOwned>(
+        &self,
+        operation_name: &str,
+        query: &str,
+        variables: Value,
+    ) -> Result<T> {
+        let body = json!({
+            "operationName": operation_name,
+            "variables": variables,
+            "query": query,
+        });
+        let mut response = self.post(&self.config.urls.graphql, &body, || None).await?;
+        debug!("{} response: {}", operation_name, response);
+
+        if let Some(errors) = response["errors"].as_array().filter(|e| !e.is_empty()) {
+            let messages: Vec<&str> = errors
+                .iter()
+                .filter_map(|error| error["message"].as_str())
+                .collect();
+            return Err(LeetUpError::GraphQL(messages.join(", ")));
+        }
+
+        Ok(serde_json::from_value(response["data"].take())?)
+    }
+
+    /// Send a request, logging its url and the response status.
+    async fn send(&self, request: RequestBuilder) -> reqwest::Result<Response> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        info!("{} {}", request.method(), request.url());
+        let start = Instant::now();
+        let result = client.execute(request).await;
+        match &result {
+            Ok(res) => info!("{} {} in {:?}", res.status(), res.url(), start.elapsed()),
+            Err(e) => info!("Request failed after {:?}: {}", start.elapsed(), e),
+        }
+
+        result
+    }
+
+    fn client(&self, headers: HeaderMap) -> Result<Client> {
+        Client::builder()
+            .default_headers(headers)
+            .timeout(self.config.request_timeout())
+            .build()
+            .map_err(LeetUpError::Reqwest)
+    }
+
+    /// Send the request built by `make_request`, retrying timeouts,
+    /// connection errors and 5xx responses with exponential backoff.
+    ///
+    /// A 429 is retried once after the `Retry-After` delay when enabled in
+    /// the config. Other non-success responses fail right away with their body.
+    /// A 401 or a redirect to the login page of an `authenticated` request
+    /// means the session has expired.
+    async fn send_with_retry<F>(&self, authenticated: bool, make_request: F) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        if self.config.offline {
+            return Err(LeetUpError::Offline("This command".into()));
+        }
+        let attempts = self.config.request_attempts();
+        let mut backoff = RETRY_BACKOFF;
+        let mut attempt = 0;
+        let mut rate_limited = false;
+
+        loop {
+            attempt += 1;
+            let last = attempt >= attempts;
+            match self.send(make_request()).await {
+                Ok(res) if authenticated && is_login_response(&res) => {
+                    return Err(LeetUpError::SessionExpired);
+                }
+                Ok(res)
+                    if res.status() == StatusCode::TOO_MANY_REQUESTS
+                        && self.config.retry_rate_limited
+                        && !rate_limited =>
+                {
+                    rate_limited = true;
+                    attempt -= 1;
+                    let wait = retry_after(res.headers()).unwrap_or(RATE_LIMIT_COOLDOWN);
+                    eprintln!(
+                        "Rate limited by LeetCode, retrying in {} seconds...",
+                        wait.as_secs()
+                    );
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+                Ok(res) if res.status().is_server_error() && !last => {
+                    warn!("Attempt {}: status {}, retrying", attempt, res.status());
+                }
+                Ok(res) if res.status().is_success() || res.status().is_redirection() => {
+                    return Ok(res);
+                }
+                Ok(res) => {
+                    let status = res.status().as_u16();
+                    let body = res.text().await.unwrap_or_default();
+                    return Err(LeetUpError::Http { status, body });
+                }
+                Err(e) if (e.is_timeout() || e.is_connect()) && !last => {
+                    warn!("Attempt {}: {}, retrying", attempt, e);
+                }
+                Err(e) => return Err(LeetUpError::Reqwest(e)),
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
         }
     }
 
@@ -62,19 +248,259 @@ impl<'a> RemoteClient<'_> {
         &self,
         headers_opt: Option<HeaderMap>,
         session: Option<&Session>,
-    ) -> HeaderMap {
+    ) -> Result<HeaderMap> {
         let mut headers = headers_opt.unwrap_or_default();
 
         if let Some(session) = session {
             let cookie: String = session.into();
-            headers.insert("Cookie", HeaderValue::from_str(&cookie).unwrap());
-            headers.insert("X-CSRFToken", HeaderValue::from_str(&session.csrf).unwrap());
+            headers.insert("Cookie", HeaderValue::from_str(&cookie)?);
+            headers.insert("X-CSRFToken", HeaderValue::from_str(&session.csrf)?);
             headers.insert(
                 "X-Requested-With",
                 HeaderValue::from_static("XMLHttpRequest"),
             );
         }
 
-        headers
+        Ok(headers)
+    }
+}
+
+/// Whether LeetCode rejected the session, either with a 401 or by
+/// redirecting to its login page.
+fn is_login_response(res: &Response) -> bool {
+    res.status() == StatusCode::UNAUTHORIZED || res.url().path().starts_with("/accounts/login")
+}
+
+/// Delay requested by the `Retry-After` header, in seconds.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    /// Serve one canned response per connection, in order.
+    fn serve(responses: Vec<String>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0; 4096];
+                let _ = stream.read(&mut buf);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        format!("http://{}/", addr)
+    }
+
+    fn ok(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    }
+
+    #[tokio::test]
+    async fn test_retry_server_error() {
+        let url = serve(vec![
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string(),
+            ok("ok"),
+        ]);
+        let mut config = Config::get("").unwrap();
+        config.request_attempts = Some(2);
+        let client = RemoteClient::new(&config, None);
+
+        let res = client.get(&url, None, None).await.unwrap();
+        assert_eq!(res.text().await.unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_client_error() {
+        let url = serve(vec![
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 7\r\nConnection: close\r\n\r\nmissing"
+                .to_string(),
+        ]);
+        let mut config = Config::get("").unwrap();
+        config.request_attempts = Some(3);
+        let client = RemoteClient::new(&config, None);
+
+        let err = client.get(&url, None, None).await.unwrap_err();
+        assert!(
+            matches!(err, LeetUpError::Http { status: 404, ref body } if body == "missing"),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(header::RETRY_AFTER, HeaderValue::from_static("3"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+
+        headers.insert(
+            header::RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn test_retry_rate_limited() {
+        let url = serve(vec![
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+            ok("ok"),
+        ]);
+        let mut config = Config::get("").unwrap();
+        config.request_attempts = Some(1);
+        config.retry_rate_limited = true;
+        let client = RemoteClient::new(&config, None);
+
+        let res = client.get(&url, None, None).await.unwrap();
+        assert_eq!(res.text().await.unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn test_session_expired() {
+        let unauthorized =
+            "HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        let login_redirect = "HTTP/1.1 302 Found\r\nLocation: /accounts/login/?next=/\r\n\
+                              Content-Length: 0\r\nConnection: close\r\n\r\n";
+        let url = serve(vec![
+            unauthorized.to_string(),
+            login_redirect.to_string(),
+            ok("login page"),
+            unauthorized.to_string(),
+        ]);
+        let config = Config::get("").unwrap();
+        let session = Session::new("expired".into(), "csrf".into());
+        let client = RemoteClient::new(&config, Some(&session));
+
+        for _ in 0..2 {
+            let err = client.get(&url, None, Some(&session)).await.unwrap_err();
+            assert!(matches!(err, LeetUpError::SessionExpired), "{:?}", err);
+        }
+        let err = client.get(&url, None, None).await.unwrap_err();
+        assert!(
+            matches!(err, LeetUpError::Http { status: 401, .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_graphql() {
+        let data = r#"{"data":{"question":{"sampleTestCase":"[1]"}}}"#;
+        let errors = r#"{"data":null,"errors":[{"message":"bad slug"}]}"#;
+        let url = serve(vec![ok(data), ok(errors)]);
+        let mut config = Config::get("").unwrap();
+        config.urls.graphql = url;
+        let client = RemoteClient::new(&config, None);
+
+        let value: Value = client
+            .graphql("getQuestionDetail", "query", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(value["question"]["sampleTestCase"], "[1]");
+
+        let err = client
+            .graphql::<Value>("getQuestionDetail", "query", json!({}))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, LeetUpError::GraphQL(ref message) if message == "bad slug"),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_json() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Count {
+            count: usize,
+        }
+        let url = serve(vec![ok(r#"{"count":3}"#), ok(r#"{"total":3}"#)]);
+        let config = Config::get("").unwrap();
+        let client = RemoteClient::new(&config, None);
+
+        let count: Count = client.get_json(&url, None).await.unwrap();
+        assert_eq!(count.count, 3);
+
+        let err = client.get_json::<Count>(&url, None).await.unwrap_err();
+        assert!(matches!(err, LeetUpError::Serde(_)), "{:?}", err);
+    }
+
+    #[test]
+    fn test_session_headers() {
+        let config = Config::get("").unwrap();
+        let session = Session::new("abc".into(), "token".into());
+        let client = RemoteClient::new(&config, Some(&session));
+
+        let headers = client.headers_with_session(None, client.session).unwrap();
+        let cookie: String = (&session).into();
+        assert_eq!(headers["Cookie"], cookie.as_str());
+        assert_eq!(headers["X-CSRFToken"], "token");
+
+        let anonymous = RemoteClient::new(&config, None);
+        assert!(anonymous
+            .headers_with_session(None, anonymous.session)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_if_changed() {
+        let url = serve(vec![
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}"
+                .to_string(),
+            "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".to_string(),
+            ok("[]"),
+        ]);
+        let config = Config::get("").unwrap();
+        let client = RemoteClient::new(&config, None);
+
+        assert_eq!(
+            client.get_if_changed(&url, None, None).await.unwrap(),
+            Fetched::Modified {
+                body: "{}".to_string(),
+                etag: Some("\"v1\"".to_string())
+            }
+        );
+        assert_eq!(
+            client
+                .get_if_changed(&url, None, Some("\"v1\""))
+                .await
+                .unwrap(),
+            Fetched::NotModified
+        );
+        assert_eq!(
+            client
+                .get_if_changed(&url, None, Some("\"v1\""))
+                .await
+                .unwrap(),
+            Fetched::Modified {
+                body: "[]".to_string(),
+                etag: None
+            }
+        );
     }
 }
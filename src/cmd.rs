@@ -1,40 +1,345 @@
-use std::path::PathBuf;
+use std::convert::TryFrom;
+use std::env;
+use std::io;
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::{Chars, FromStr};
 
+use anyhow::anyhow;
 use leetup_cache::kvstore::KvStore;
-use log::debug;
+use log::{debug, info};
 use spinners::{Spinner, Spinners};
-use structopt::StructOpt;
+use structopt::{clap::Shell, StructOpt};
 
 use crate::service::{CacheKey, Session};
+use crate::{clean, color, history, interrupt};
 use crate::{
     service::{leetcode::Leetcode, Lang, ServiceProvider},
-    Config, Result,
+    Config, FilterProfile, LeetUpError, Result, Site,
 };
 
-#[derive(Debug, StructOpt)]
+/// Environment variable pointing at an alternative config file.
+const CONFIG_ENV: &str = "LEETUP_CONFIG";
+
+#[derive(Debug, Clone, StructOpt)]
 pub struct List {
     pub keyword: Option<String>,
 
-    /// Filter by given tag
-    #[structopt(short, long)]
-    pub tag: Option<String>,
+    /// Start from a filter profile defined in the config, other flags override it
+    #[structopt(long)]
+    pub profile: Option<String>,
+
+    /// Filter by given tag, repeat to require several tags
+    #[structopt(short, long, number_of_values = 1)]
+    pub tag: Vec<String>,
 
     /// Query by conditions
     #[structopt(short, long)]
     pub query: Option<String>,
 
+    /// Only solved problems, same as `-q d`
+    #[structopt(long, conflicts_with = "unsolved")]
+    pub solved: bool,
+
+    /// Only unsolved problems, same as `-q D`
+    #[structopt(long)]
+    pub unsolved: bool,
+
+    /// Only free problems, same as `-q L`
+    #[structopt(long, conflicts_with = "paid")]
+    pub free: bool,
+
+    /// Only premium problems, same as `-q l`
+    #[structopt(long)]
+    pub paid: bool,
+
+    /// Only problems LeetCode marks as new
+    #[structopt(long)]
+    pub new: bool,
+
+    /// Only problems you wrote a note about
+    #[structopt(long)]
+    pub has_note: bool,
+
+    /// Filter by company tag, requires login
+    #[structopt(short, long)]
+    pub company: Option<String>,
+
     /// Show statistic counter of the output list
     #[structopt(short, long)]
     pub stat: bool,
 
-    /// Order by ProblemId, Question Title, or Difficulty
+    /// Order by ProblemId, Question Title, Difficulty, Acceptance, Frequency or New
     #[structopt(short, long)]
     pub order: Option<String>,
+
+    /// Run queries that can never match, e.g. easy and medium
+    #[structopt(long)]
+    pub force: bool,
+
+    /// Print each problem with a template, e.g. "{id}\t{title}\t{difficulty}"
+    #[structopt(long)]
+    pub format: Option<String>,
+
+    /// Table columns to show, in order, e.g. "id,title,difficulty"
+    #[structopt(long, conflicts_with = "format")]
+    pub fields: Option<String>,
+
+    /// Only print the problem IDs, one per line, e.g. to pipe into `pick`
+    #[structopt(long, conflicts_with_all = &["format", "fields", "count"])]
+    pub only_ids: bool,
+
+    /// Only print how many problems match, ignoring --limit
+    #[structopt(long)]
+    pub count: bool,
+
+    /// Print the list directly instead of through $PAGER
+    #[structopt(long)]
+    pub no_pager: bool,
+
+    /// Reverse the sort order, with or without --order
+    #[structopt(long)]
+    pub reverse: bool,
+
+    /// Ignore cached problems and fetch them again
+    #[structopt(short, long, visible_alias = "no-cache")]
+    pub refresh: bool,
+
+    /// Show at most N problems after filtering, 0 means unlimited
+    #[structopt(short = "n", long)]
+    pub limit: Option<usize>,
+
+    /// Only problems with an ID in an inclusive range, e.g. 1-100 or 200-
+    #[structopt(long)]
+    pub range: Option<IdRange>,
+
+    /// Output format: table, json or csv
+    #[structopt(long, default_value = "table")]
+    pub output: OutputFormat,
+
+    /// Only list problems of a category: algorithms, database, shell or concurrency
+    #[structopt(long, conflicts_with_all = &["tag", "all-categories"])]
+    pub category: Option<Category>,
+
+    /// List problems of every category, showing the category of each
+    #[structopt(long, conflicts_with = "tag")]
+    pub all_categories: bool,
+}
+
+impl List {
+    /// `--query` merged with the `--solved`, `--unsolved`, `--free` and `--paid` flags.
+    pub fn query(&self) -> Option<String> {
+        let mut query = self.query.clone().unwrap_or_default();
+        for (flag, symbol) in &[
+            (self.solved, 'd'),
+            (self.unsolved, 'D'),
+            (self.free, 'L'),
+            (self.paid, 'l'),
+        ] {
+            if *flag {
+                query.push(*symbol);
+            }
+        }
+
+        Some(query).filter(|query| !query.is_empty())
+    }
+
+    /// Whether the query filters on the solved status, which LeetCode only
+    /// sends to logged in users.
+    pub fn filters_status(&self) -> Result<bool> {
+        let queries = match self.query() {
+            Some(query) => Query::from_str(&query)?,
+            None => return Ok(false),
+        };
+
+        Ok(queries
+            .iter()
+            .any(|q| matches!(q, Query::Done | Query::NotDone)))
+    }
+
+    /// Filters of `profile` where no flag overrides them.
+    pub fn with_profile(&self, profile: &FilterProfile) -> List {
+        let mut list = self.clone();
+        if list.query.is_none() {
+            list.query = profile.query.to_owned();
+        }
+        if list.tag.is_empty() {
+            list.tag = profile.tags.to_owned();
+        }
+        if list.order.is_none() {
+            list.order = profile.order.to_owned();
+        }
+        list
+    }
+
+    /// Categories to fetch instead of the default problem list, if any.
+    pub fn categories(&self) -> Option<Vec<Category>> {
+        if self.all_categories {
+            Some(Category::ALL.to_vec())
+        } else {
+            self.category.map(|category| vec![category])
+        }
+    }
+}
+
+/// Problem categories LeetCode lists separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Algorithms,
+    Database,
+    Shell,
+    Concurrency,
+}
+
+impl Category {
+    pub const ALL: [Category; 4] = [
+        Category::Algorithms,
+        Category::Database,
+        Category::Shell,
+        Category::Concurrency,
+    ];
+
+    /// Category as used in LeetCode URLs.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Category::Algorithms => "algorithms",
+            Category::Database => "database",
+            Category::Shell => "shell",
+            Category::Concurrency => "concurrency",
+        }
+    }
+}
+
+impl FromStr for Category {
+    type Err = LeetUpError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Category::ALL
+            .iter()
+            .find(|category| category.slug() == s)
+            .copied()
+            .ok_or_else(|| {
+                LeetUpError::Any(anyhow!(
+                    "Unknown category `{}`, expected one of: algorithms, database, shell, concurrency",
+                    s
+                ))
+            })
+    }
+}
+
+/// Inclusive range of problem IDs, open ended when `end` is `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl IdRange {
+    pub fn contains(&self, id: usize) -> bool {
+        id >= self.start && self.end.is_none_or(|end| id <= end)
+    }
+}
+
+impl FromStr for IdRange {
+    type Err = LeetUpError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || {
+            LeetUpError::Any(anyhow!(
+                "Invalid range `{}`, expected START-END or START-, e.g. 1-100 or 200-",
+                s
+            ))
+        };
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let start = start.trim().parse::<usize>().map_err(|_| invalid())?;
+        let end = match end.trim() {
+            "" => None,
+            end => Some(end.parse::<usize>().map_err(|_| invalid())?),
+        };
+        if end.is_some_and(|end| end < start) {
+            return Err(LeetUpError::Any(anyhow!(
+                "Invalid range `{}`, the start is past the end",
+                s
+            )));
+        }
+
+        Ok(IdRange { start, end })
+    }
+}
+
+/// Output format of listed problems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = LeetUpError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(LeetUpError::Any(anyhow!(
+                "Unknown output format `{}`, expected one of: table, json, csv",
+                s
+            ))),
+        }
+    }
+}
+
+/// File format written by `export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON array
+    Json,
+    /// One JSON object per line
+    Ndjson,
+}
+
+impl FromStr for ExportFormat {
+    type Err = LeetUpError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(ExportFormat::Json),
+            "ndjson" => Ok(ExportFormat::Ndjson),
+            _ => Err(LeetUpError::Any(anyhow!(
+                "Unknown export format `{}`, expected one of: json, ndjson",
+                s
+            ))),
+        }
+    }
+}
+
+/// Language of problem descriptions, Chinese is only available on leetcode.cn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentLang {
+    En,
+    Zh,
+}
+
+impl FromStr for ContentLang {
+    type Err = LeetUpError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "en" => Ok(ContentLang::En),
+            "zh" => Ok(ContentLang::Zh),
+            _ => Err(LeetUpError::Any(anyhow!(
+                "Unknown content language `{}`, expected one of: en, zh",
+                s
+            ))),
+        }
+    }
 }
 
 #[derive(Debug, StructOpt)]
 pub struct User {
-    /// Login using cookie
+    /// Login using cookie, optionally read from an exported cookies file
     #[structopt(short, long)]
     pub cookie: Option<Option<String>>,
 
@@ -45,8 +350,8 @@ pub struct User {
 
 #[derive(Debug, StructOpt)]
 pub struct Pick {
-    /// Show/Pick a problem using ID.
-    pub id: Option<usize>,
+    /// Show/Pick a problem using ID, title slug or a keyword from the title.
+    pub id: Option<String>,
 
     /// Generate code if true.
     #[structopt(short)]
@@ -59,12 +364,280 @@ pub struct Pick {
     /// Language used to generate problem's source.
     #[structopt(short, long)]
     pub lang: Option<Lang>,
+
+    /// Pick every problem ID or slug listed in a file, one per line.
+    #[structopt(long, conflicts_with = "id")]
+    pub batch: Option<String>,
+
+    /// Open the generated file with $EDITOR
+    #[structopt(long, conflicts_with = "batch")]
+    pub open_editor: bool,
+
+    /// Language of the problem description: en, or zh on leetcode.cn
+    #[structopt(long, default_value = "en")]
+    pub content_lang: ContentLang,
+
+    /// Overwrite the file if it already exists
+    #[structopt(long)]
+    pub force: bool,
+}
+
+impl Pick {
+    /// Pick problem `id` with the default flags, as `leetup pick <id>`
+    /// would, in `lang` if given.
+    pub fn for_id(id: String, lang: Option<Lang>) -> Self {
+        Pick {
+            id: Some(id),
+            generate: false,
+            def: false,
+            lang,
+            batch: None,
+            open_editor: false,
+            content_lang: ContentLang::En,
+            force: false,
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Export {
+    /// File to write, stdout if omitted
+    #[structopt(short, long)]
+    pub output: Option<String>,
+
+    /// Output format: json or ndjson
+    #[structopt(long, default_value = "json")]
+    pub format: ExportFormat,
+
+    /// Add each problem's topic tags, fetched once and cached
+    #[structopt(long)]
+    pub tags: bool,
+
+    /// Indent the JSON, ignored for ndjson
+    #[structopt(long)]
+    pub pretty: bool,
+
+    /// Print the JSON Schema of an exported problem instead
+    #[structopt(long, conflicts_with_all = &["output", "tags"])]
+    pub schema: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Stats {
+    /// Number of weeks shown in the solved per week sparkline, up to 520
+    #[structopt(short, long, default_value = "12", parse(try_from_str = parse_weeks))]
+    pub weeks: usize,
+
+    /// Width in percent of the acceptance rate ranges problems are counted in
+    #[structopt(long, default_value = "20", parse(try_from_str = parse_bucket_width))]
+    pub bucket_width: u32,
+}
+
+/// Ten years of weeks, more than any terminal is wide.
+const MAX_WEEKS: usize = 520;
+
+/// Sparkline length in weeks, at most `MAX_WEEKS`.
+fn parse_weeks(s: &str) -> Result<usize> {
+    match s.parse::<usize>() {
+        Ok(weeks) if weeks <= MAX_WEEKS => Ok(weeks),
+        _ => Err(LeetUpError::Any(anyhow!(
+            "Invalid number of weeks `{}`, expected at most {}",
+            s,
+            MAX_WEEKS
+        ))),
+    }
+}
+
+/// Acceptance bucket width, between 1 and 100 percent.
+fn parse_bucket_width(s: &str) -> Result<u32> {
+    match s.parse::<u32>() {
+        Ok(width) if (1..=100).contains(&width) => Ok(width),
+        _ => Err(LeetUpError::Any(anyhow!(
+            "Invalid bucket width `{}`, expected a percentage between 1 and 100",
+            s
+        ))),
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct History {
+    /// Number of submissions to show, newest first
+    #[structopt(short = "n", long, default_value = "20")]
+    pub limit: usize,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Open {
+    /// Problem ID, title slug or a keyword from the title.
+    pub id: String,
+
+    /// Print the URL instead of opening it
+    #[structopt(short, long)]
+    pub print: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Show {
+    /// Problem ID, title slug or a keyword from the title.
+    pub id: String,
+
+    /// Print the problem's hints instead of its description
+    #[structopt(long)]
+    pub hints: bool,
+
+    /// Print only the Nth hint, starting at 1
+    #[structopt(long, conflicts_with = "hints")]
+    pub hint: Option<usize>,
+
+    /// Language of the description: en, or zh on leetcode.cn
+    #[structopt(long, default_value = "en")]
+    pub content_lang: ContentLang,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Similar {
+    /// Problem ID, title slug or a keyword from the title.
+    pub id: String,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct TagsReport {
+    /// Count the tags of unsolved problems instead, to find gaps
+    #[structopt(long)]
+    pub unsolved: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Info {
+    /// Problem ID, title slug or a keyword from the title.
+    pub id: String,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Star {
+    /// Problem ID, title slug or a keyword from the title.
+    #[structopt(required_unless = "query")]
+    pub id: Option<String>,
+
+    /// Every problem matching a query instead, same as list, e.g. `-q HD`
+    #[structopt(short, long, conflicts_with = "id")]
+    pub query: Option<String>,
+
+    /// Don't ask for confirmation before changing many problems
+    #[structopt(short, long, requires = "query")]
+    pub yes: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Note {
+    /// Problem ID, title slug or a keyword from the title.
+    pub id: String,
+
+    /// Print the note instead of editing it
+    #[structopt(long)]
+    pub show: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Random {
+    /// Query by conditions, same as list
+    #[structopt(short, long)]
+    pub query: Option<String>,
+
+    /// Pick the selected problem.
+    #[structopt(short, long)]
+    pub pick: bool,
+
+    /// Language used to generate problem's source.
+    #[structopt(short, long)]
+    pub lang: Option<Lang>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Next {
+    /// Query by conditions, same as list, always limited to unsolved problems
+    #[structopt(short, long, default_value = "eL")]
+    pub query: String,
+
+    /// Language used to generate problem's source.
+    #[structopt(short, long)]
+    pub lang: Option<Lang>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Daily {
+    /// Pick today's problem.
+    #[structopt(short, long)]
+    pub pick: bool,
+
+    /// Language used to generate problem's source.
+    #[structopt(short, long)]
+    pub lang: Option<Lang>,
+
+    /// Keep checking for a new daily challenge until Ctrl-C
+    #[structopt(short, long, conflicts_with = "pick")]
+    pub watch: bool,
+
+    /// Minutes between two checks with --watch, 15 by default
+    #[structopt(long, requires = "watch")]
+    pub interval: Option<u64>,
+
+    /// Also send a desktop notification when a new challenge appears
+    #[structopt(long, requires = "watch")]
+    pub notify: bool,
 }
 
 #[derive(Debug, StructOpt)]
 pub struct Submit {
-    /// Code filename.
-    pub filename: String,
+    /// Code filename, defaults to the file of the last picked problem.
+    pub filename: Option<String>,
+
+    /// Read the solution from stdin instead of a file, requires --lang and --id
+    #[structopt(long, requires_all = &["lang", "id"], conflicts_with = "filename")]
+    pub stdin: bool,
+
+    /// Language of the solution, overrides the one the file was picked in
+    #[structopt(short, long)]
+    pub lang: Option<Lang>,
+
+    /// Problem ID or title slug, of the picked file or of the --stdin code
+    #[structopt(long, conflicts_with = "filename")]
+    pub id: Option<String>,
+
+    /// Print what would be submitted without sending it
+    #[structopt(long)]
+    pub dry_run: bool,
+
+    /// Submit with --lang even if the file extension doesn't match it
+    #[structopt(long, requires = "lang")]
+    pub force: bool,
+
+    /// Seconds between two judge result polls, 1 by default
+    #[structopt(long)]
+    pub poll_interval: Option<f64>,
+
+    /// Seconds to wait for the judge result, 30 by default
+    #[structopt(long)]
+    pub poll_timeout: Option<u64>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct SubmissionResult {
+    /// Submission ID, as printed by submit and on the website
+    pub submission_id: u64,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Diff {
+    /// Problem ID, title slug or a keyword from the title.
+    pub id: String,
+
+    /// Solution file, defaults to the one `pick` generates for the problem
+    pub filename: Option<String>,
+
+    /// Language of the solution file `pick` generated, defaults to the configured one
+    #[structopt(short, long, conflicts_with = "filename")]
+    pub lang: Option<Lang>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -72,9 +645,25 @@ pub struct Test {
     /// Code filename.
     pub filename: String,
 
-    /// Custom test cases.
-    #[structopt(short)]
+    /// Custom test cases. Uses the problem's sample test case if omitted.
+    #[structopt(short, long = "testcase")]
     pub test_data: Option<Option<String>>,
+
+    /// Print what would be tested without sending it
+    #[structopt(long)]
+    pub dry_run: bool,
+
+    /// Edit the test cases with $EDITOR, starting from the last ones used
+    #[structopt(long, conflicts_with = "test-data")]
+    pub edit: bool,
+
+    /// Seconds between two judge result polls, 1 by default
+    #[structopt(long)]
+    pub poll_interval: Option<f64>,
+
+    /// Seconds to wait for the judge result, 30 by default
+    #[structopt(long)]
+    pub poll_timeout: Option<u64>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -87,17 +676,120 @@ pub enum Command {
     #[structopt(name = "user")]
     User(User),
 
+    /// Show who is logged in, on which site, and when the session expires
+    #[structopt(name = "whoami")]
+    Whoami,
+
+    /// Browse questions interactively and pick one, takes the same options as list
+    #[cfg(feature = "tui")]
+    #[structopt(name = "browse")]
+    Browse(List),
+
     /// Pick a problem
     #[structopt(name = "pick")]
     Pick(Pick),
 
+    /// Show a problem's description
+    #[structopt(name = "show")]
+    Show(Show),
+
+    /// Open a problem in the browser
+    #[structopt(name = "open")]
+    Open(Open),
+
+    /// Pick a random problem
+    #[structopt(name = "random")]
+    Random(Random),
+
+    /// Pick the unsolved problem with the lowest ID, easy and free by default
+    #[structopt(name = "next")]
+    Next(Next),
+
+    /// Show the daily challenge
+    #[structopt(name = "daily")]
+    Daily(Daily),
+
+    /// Show a problem's metadata at a glance
+    #[structopt(name = "info")]
+    Info(Info),
+
+    /// List problems similar to a problem
+    #[structopt(name = "similar")]
+    Similar(Similar),
+
+    /// Edit or show your markdown note about a problem
+    #[structopt(name = "note")]
+    Note(Note),
+
+    /// Add a problem to your favorites
+    #[structopt(name = "star")]
+    Star(Star),
+
+    /// Remove a problem from your favorites
+    #[structopt(name = "unstar")]
+    Unstar(Star),
+
+    /// Write every problem to a JSON file, for archival and other tools
+    #[structopt(name = "export")]
+    Export(Export),
+
+    /// Show solved problems per difficulty
+    #[structopt(name = "stats")]
+    Stats(Stats),
+
+    /// Show recent submissions
+    #[structopt(name = "history")]
+    History(History),
+
+    /// Count solved problems per topic tag
+    #[structopt(name = "tags-report")]
+    TagsReport(TagsReport),
+
+    /// Show the current and longest streak of days with an accepted submission
+    #[structopt(name = "streak")]
+    Streak,
+
     /// Submit a problem
     #[structopt(name = "submit")]
     Submit(Submit),
 
+    /// Show the verdict of a past submission, e.g. after submit timed out
+    #[structopt(name = "result")]
+    Result(SubmissionResult),
+
     /// Test a problem
     #[structopt(name = "test")]
     Test(Test),
+
+    /// Compare a local solution with your last accepted submission
+    #[structopt(name = "diff")]
+    Diff(Diff),
+
+    /// Print a shell completion script
+    #[structopt(name = "completions")]
+    Completions(Completions),
+
+    /// Remove cached problems, keeping the session
+    #[structopt(name = "clean")]
+    Clean(Clean),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Clean {
+    /// Also remove the session and the config file in use
+    #[structopt(long)]
+    pub all: bool,
+
+    /// List the files that would be removed
+    #[structopt(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Completions {
+    /// Shell to generate completions for
+    #[structopt(possible_values = &Shell::variants(), case_insensitive = true)]
+    pub shell: Shell,
 }
 
 /// -q to query by conditions.
@@ -107,9 +799,10 @@ pub enum Command {
 ///    d = done = AC-ed, D = not AC-ed.
 ///    l = locked, L = not locked.
 ///    s = starred, S = unstarred.
-#[derive(Debug)]
+///    a>N = acceptance above N%, a<N = acceptance below N%.
+#[derive(Debug, PartialEq)]
 pub enum Query {
-    Easy = 1,
+    Easy,
     Medium,
     Hard,
     NotEasy,
@@ -121,34 +814,142 @@ pub enum Query {
     NotDone,
     Starred,
     Unstarred,
+    AcceptanceAbove(f64),
+    AcceptanceBelow(f64),
 }
 
-impl From<char> for Query {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Query {
+    type Error = LeetUpError;
+
+    fn try_from(c: char) -> Result<Self> {
         match c {
-            'e' => Query::Easy,
-            'E' => Query::NotEasy,
-            'm' => Query::Medium,
-            'M' => Query::NotMedium,
-            'h' => Query::Hard,
-            'H' => Query::NotHard,
-            'l' => Query::Locked,
-            'L' => Query::Unlocked,
-            'd' => Query::Done,
-            'D' => Query::NotDone,
-            's' => Query::Starred,
-            'S' => Query::Unstarred,
-            _ => Query::Easy,
+            'e' => Ok(Query::Easy),
+            'E' => Ok(Query::NotEasy),
+            'm' => Ok(Query::Medium),
+            'M' => Ok(Query::NotMedium),
+            'h' => Ok(Query::Hard),
+            'H' => Ok(Query::NotHard),
+            'l' => Ok(Query::Locked),
+            'L' => Ok(Query::Unlocked),
+            'd' => Ok(Query::Done),
+            'D' => Ok(Query::NotDone),
+            's' => Ok(Query::Starred),
+            'S' => Ok(Query::Unstarred),
+            _ => Err(LeetUpError::Any(anyhow!(
+                "Unknown query `{}`, expected one of: e, E, m, M, h, H, l, L, d, D, s, S, a>N, a<N",
+                c
+            ))),
         }
     }
 }
 
 impl Query {
-    pub fn from_str(q: &str) -> Vec<Query> {
-        q.chars().map(Query::from).collect()
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(q: &str) -> Result<Vec<Query>> {
+        let mut queries = vec![];
+        let mut chars = q.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                'a' => queries.push(Query::parse_acceptance(&mut chars)?),
+                c => queries.push(Query::try_from(c)?),
+            }
+        }
+
+        Ok(queries)
+    }
+
+    /// Error out on queries that can never match together, e.g. `em`.
+    pub fn check_conflicts(queries: &[Query]) -> Result<()> {
+        use Query::*;
+
+        let has = |q: &Query| queries.contains(q);
+        let conflict = |a: &Query, b: &Query| {
+            Err(LeetUpError::ConflictingQuery(format!(
+                "`{}` and `{}`",
+                a.symbol(),
+                b.symbol()
+            )))
+        };
+        let exclusive = [
+            (Easy, NotEasy),
+            (Medium, NotMedium),
+            (Hard, NotHard),
+            (Easy, Medium),
+            (Easy, Hard),
+            (Medium, Hard),
+            (Locked, Unlocked),
+            (Done, NotDone),
+            (Starred, Unstarred),
+        ];
+        for (a, b) in &exclusive {
+            if has(a) && has(b) {
+                return conflict(a, b);
+            }
+        }
+        if has(&NotEasy) && has(&NotMedium) && has(&NotHard) {
+            return Err(LeetUpError::ConflictingQuery("`E`, `M` and `H`".into()));
+        }
+
+        for above in queries {
+            for below in queries {
+                if let (AcceptanceAbove(min), AcceptanceBelow(max)) = (above, below) {
+                    if min >= max {
+                        return conflict(above, below);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Query as written on the command line.
+    fn symbol(&self) -> String {
+        match self {
+            Query::Easy => "e".into(),
+            Query::NotEasy => "E".into(),
+            Query::Medium => "m".into(),
+            Query::NotMedium => "M".into(),
+            Query::Hard => "h".into(),
+            Query::NotHard => "H".into(),
+            Query::Locked => "l".into(),
+            Query::Unlocked => "L".into(),
+            Query::Done => "d".into(),
+            Query::NotDone => "D".into(),
+            Query::Starred => "s".into(),
+            Query::Unstarred => "S".into(),
+            Query::AcceptanceAbove(n) => format!("a>{}", n),
+            Query::AcceptanceBelow(n) => format!("a<{}", n),
+        }
+    }
+
+    /// Parse the `>N` or `<N` part of an acceptance query.
+    fn parse_acceptance(chars: &mut Peekable<Chars>) -> Result<Query> {
+        let op = chars.next();
+        let mut threshold = String::new();
+        while let Some(&c) = chars.peek() {
+            if !c.is_ascii_digit() && c != '.' {
+                break;
+            }
+            threshold.push(c);
+            chars.next();
+        }
+        let invalid = || {
+            LeetUpError::Any(anyhow!(
+                "Invalid acceptance query, expected `a>N` or `a<N` with N in percent"
+            ))
+        };
+        let threshold: f64 = threshold.parse().map_err(|_| invalid())?;
+
+        match op {
+            Some('>') => Ok(Query::AcceptanceAbove(threshold)),
+            Some('<') => Ok(Query::AcceptanceBelow(threshold)),
+            _ => Err(invalid()),
+        }
     }
 }
 
+#[derive(Debug, PartialEq)]
 pub enum OrderBy {
     /// Order by question Id in Ascending order
     IdAsc,
@@ -159,75 +960,372 @@ pub enum OrderBy {
     TitleDesc,
     DifficultyAsc,
     DifficultyDesc,
+    AcceptanceAsc,
+    AcceptanceDesc,
+    FrequencyAsc,
+    FrequencyDesc,
+
+    /// Order new questions last
+    NewAsc,
+
+    /// Order new questions first
+    NewDesc,
 }
 
-impl From<char> for OrderBy {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for OrderBy {
+    type Error = LeetUpError;
+
+    fn try_from(c: char) -> Result<Self> {
         match c {
-            'i' => OrderBy::IdAsc,
-            'I' => OrderBy::IdDesc,
-            't' => OrderBy::TitleAsc,
-            'T' => OrderBy::TitleDesc,
-            'd' => OrderBy::DifficultyAsc,
-            'D' => OrderBy::DifficultyDesc,
-            _ => OrderBy::IdAsc,
+            'i' => Ok(OrderBy::IdAsc),
+            'I' => Ok(OrderBy::IdDesc),
+            't' => Ok(OrderBy::TitleAsc),
+            'T' => Ok(OrderBy::TitleDesc),
+            'd' => Ok(OrderBy::DifficultyAsc),
+            'D' => Ok(OrderBy::DifficultyDesc),
+            'a' => Ok(OrderBy::AcceptanceAsc),
+            'A' => Ok(OrderBy::AcceptanceDesc),
+            'f' => Ok(OrderBy::FrequencyAsc),
+            'F' => Ok(OrderBy::FrequencyDesc),
+            'n' => Ok(OrderBy::NewAsc),
+            'N' => Ok(OrderBy::NewDesc),
+            _ => Err(LeetUpError::Any(anyhow!(
+                "Unknown order `{}`, expected one of: i, I, t, T, d, D, a, A, f, F, n, N",
+                c
+            ))),
         }
     }
 }
 
 impl OrderBy {
-    pub fn from_str(order: &str) -> Vec<OrderBy> {
-        order.chars().map(OrderBy::from).collect()
+    /// Parse sort keys, most significant first.
+    ///
+    /// Keys are separated by commas, each either a long key such as
+    /// `difficulty` or `difficulty-desc`, ascending by default, or a run of
+    /// short keys such as `iFa`. A field may only be ordered by once.
+    ///
+    /// Without a comma, a key is only read as a long one when it ends with
+    /// `-asc` or `-desc`, so `id` keeps meaning `i` then `d`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(order: &str) -> Result<Vec<OrderBy>> {
+        let bare = !order.contains(',');
+        let mut orders: Vec<OrderBy> = vec![];
+        for token in order.split(',').map(str::trim) {
+            if token.is_empty() {
+                return Err(LeetUpError::Any(anyhow!(
+                    "Empty order key in `{}`, separate keys with a single comma",
+                    order
+                )));
+            }
+            let long = if bare && !token.contains('-') {
+                None
+            } else {
+                OrderBy::from_long(token)
+            };
+            let keys = match long {
+                Some(order) => vec![order],
+                None if token.contains('-') => {
+                    return Err(LeetUpError::Any(anyhow!(
+                        "Unknown order `{}`, expected one of: id, title, difficulty, acceptance, \
+                         frequency, new, optionally followed by -asc or -desc",
+                        token
+                    )))
+                }
+                None => token
+                    .chars()
+                    .map(OrderBy::try_from)
+                    .collect::<Result<Vec<_>>>()?,
+            };
+            for key in keys {
+                if orders.iter().any(|order| order.field() == key.field()) {
+                    return Err(LeetUpError::Any(anyhow!(
+                        "Order key `{}` is given more than once",
+                        key.field()
+                    )));
+                }
+                orders.push(key);
+            }
+        }
+
+        Ok(orders)
+    }
+
+    /// Parse a long key, e.g. `id`, `title-asc` or `acceptance-desc`.
+    fn from_long(token: &str) -> Option<OrderBy> {
+        let (field, desc) = match token.rsplit_once('-') {
+            Some((field, "asc")) => (field, false),
+            Some((field, "desc")) => (field, true),
+            _ => (token, false),
+        };
+        let order = match (field, desc) {
+            ("id", false) => OrderBy::IdAsc,
+            ("id", true) => OrderBy::IdDesc,
+            ("title", false) => OrderBy::TitleAsc,
+            ("title", true) => OrderBy::TitleDesc,
+            ("difficulty", false) => OrderBy::DifficultyAsc,
+            ("difficulty", true) => OrderBy::DifficultyDesc,
+            ("acceptance", false) => OrderBy::AcceptanceAsc,
+            ("acceptance", true) => OrderBy::AcceptanceDesc,
+            ("frequency", false) => OrderBy::FrequencyAsc,
+            ("frequency", true) => OrderBy::FrequencyDesc,
+            ("new", false) => OrderBy::NewAsc,
+            ("new", true) => OrderBy::NewDesc,
+            _ => return None,
+        };
+
+        Some(order)
+    }
+
+    /// Field ordered by, whatever the direction.
+    fn field(&self) -> &'static str {
+        match self {
+            OrderBy::IdAsc | OrderBy::IdDesc => "id",
+            OrderBy::TitleAsc | OrderBy::TitleDesc => "title",
+            OrderBy::DifficultyAsc | OrderBy::DifficultyDesc => "difficulty",
+            OrderBy::AcceptanceAsc | OrderBy::AcceptanceDesc => "acceptance",
+            OrderBy::FrequencyAsc | OrderBy::FrequencyDesc => "frequency",
+            OrderBy::NewAsc | OrderBy::NewDesc => "new",
+        }
     }
 }
 
 #[derive(StructOpt, Debug)]
 #[structopt(name = "leetup")]
 pub struct LeetUpArgs {
+    /// Disable colored output, also disabled when NO_COLOR is set
+    #[structopt(long, global = true)]
+    pub no_color: bool,
+
+    /// Site to use: leetcode-us or leetcode-cn
+    #[structopt(long, global = true)]
+    pub site: Option<Site>,
+
+    /// Log requests and cache use, repeat for more details; RUST_LOG takes precedence
+    #[structopt(short, long, global = true, parse(from_occurrences))]
+    pub verbose: u8,
+
+    /// Login with an exported cookies file before running the command
+    #[structopt(long, global = true)]
+    pub login_cookies: Option<String>,
+
+    /// Work from the cache only, without network access
+    #[structopt(long, global = true)]
+    pub offline: bool,
+
+    /// Print errors to stderr as JSON objects, for tools wrapping leetup
+    #[structopt(long, global = true)]
+    pub json_errors: bool,
+
     #[structopt(subcommand)]
     pub command: Command,
 }
 
-pub async fn process() -> Result<()> {
-    let opt = LeetUpArgs::from_args();
+pub async fn process(opt: LeetUpArgs) -> Result<()> {
+    init_logger(opt.verbose);
     debug!("Options: {:#?}", opt);
 
+    if let Command::Completions(ref completions) = opt.command {
+        LeetUpArgs::clap().gen_completions_to("leetup", completions.shell, &mut io::stdout());
+        return Ok(());
+    }
+
     let config_dir = create_config_directory()?;
+    let mut config = get_config(&config_dir)?;
+    if let Some(site) = opt.site {
+        config.set_site(site);
+    }
+    config.offline = opt.offline;
+    if config.offline {
+        match opt.command {
+            Command::Submit(ref submit) if !submit.dry_run => {
+                return Err(LeetUpError::Offline("Submitting".into()))
+            }
+            Command::Test(ref test) if !test.dry_run => {
+                return Err(LeetUpError::Offline("Testing".into()))
+            }
+            Command::User(_) => return Err(LeetUpError::Offline("Logging in or out".into())),
+            Command::Diff(_) => return Err(LeetUpError::Offline("Diffing submissions".into())),
+            Command::Result(_) => return Err(LeetUpError::Offline("Checking a submission".into())),
+            Command::Star(_) | Command::Unstar(_) => {
+                return Err(LeetUpError::Offline("Changing favorites".into()))
+            }
+            _ => {}
+        }
+    }
+    color::init(opt.no_color || config.color == Some(false));
+    if color::is_enabled() {
+        color::set_theme(color::Theme::new(&config.colors));
+    }
+
+    if let Command::Clean(ref clean) = opt.command {
+        return clean::clean(
+            &config_dir,
+            &config_path(&config_dir),
+            clean.all,
+            clean.dry_run,
+        );
+    }
+
     let mut cache = KvStore::open(&config_dir)?;
-    let session = get_session(&mut cache)?;
-    let config = get_config(config_dir);
-    debug!("Session: {:#?}", session);
+    let env_session = Session::from_env()?;
+    let session = match opt.login_cookies {
+        Some(ref path) => {
+            let session = Session::from_cookie_file(shellexpand::tilde(path).as_ref())?;
+            cache.set(CacheKey::Session.into(), serde_json::to_string(&session)?)?;
+            Some(session)
+        }
+        // Not stored, so the environment only applies while it is set.
+        None if env_session.is_some() => env_session,
+        None => get_session(&mut cache)?,
+    };
+    info!(
+        "Session: {}",
+        if session.is_some() { "present" } else { "none" }
+    );
     debug!("Config: {:#?}", config);
 
     let mut provider = Leetcode::new(session.as_ref(), &config, cache)?;
 
-    match opt.command {
+    let result = run(&mut provider, opt.command).await;
+    if let Err(LeetUpError::SessionExpired) = result {
+        // Don't keep sending the stale cookies on every command.
+        provider.logout()?;
+    }
+
+    result
+}
+
+/// Run `command` with `provider`.
+async fn run(provider: &mut Leetcode<'_>, command: Command) -> Result<()> {
+    match command {
         Command::Pick(pick) => {
             provider.pick_problem(pick).await?;
         }
         Command::List(list) => {
             provider.list_problems(list).await?;
         }
+        #[cfg(feature = "tui")]
+        Command::Browse(list) => {
+            provider.browse_problems(list).await?;
+        }
+        Command::Open(open) => {
+            provider.open_problem(open).await?;
+        }
+        Command::Show(show) => {
+            provider.show_problem(show).await?;
+        }
+        Command::Random(random) => {
+            provider.random_problem(random).await?;
+        }
+        Command::Next(next) => {
+            provider.next_problem(next).await?;
+        }
+        Command::Daily(daily) => {
+            provider.daily_problem(daily).await?;
+        }
+        Command::Info(info) => {
+            provider.problem_info(info).await?;
+        }
+        Command::Similar(similar) => {
+            provider.similar_problems(similar).await?;
+        }
+        Command::Note(note) => {
+            provider.problem_note(note).await?;
+        }
+        Command::Star(star) => {
+            provider.star_problem(star, true).await?;
+        }
+        Command::Unstar(star) => {
+            provider.star_problem(star, false).await?;
+        }
+        Command::Export(export) => {
+            provider.export_problems(export).await?;
+        }
+        Command::Stats(stats) => {
+            provider.problem_stats(stats).await?;
+        }
+        Command::Completions(_) => unreachable!("completions are printed before loading config"),
+        Command::Clean(_) => unreachable!("cleaning is done before opening the cache"),
+        Command::History(history) => {
+            history::History::open()?.print(history.limit)?;
+        }
+        Command::TagsReport(report) => {
+            provider.tags_report(report).await?;
+        }
+        Command::Streak => {
+            print!("{}", history::History::open()?.streak()?);
+        }
+        Command::Diff(diff) => {
+            provider.diff_solution(diff).await?;
+        }
+        Command::Result(result) => {
+            provider.submission_result(result).await?;
+        }
         Command::User(user) => {
             provider.process_auth(user).await?;
         }
+        Command::Whoami => {
+            provider.whoami().await?;
+        }
+        Command::Submit(submit) if submit.dry_run => {
+            provider.problem_submit(submit).await?;
+        }
+        Command::Test(test) if test.dry_run || test.edit => {
+            provider.problem_test(test).await?;
+        }
         Command::Submit(submit) => {
+            interrupt::catch_ctrl_c();
             let sp = Spinner::new(Spinners::Dots9, "Waiting for judge result!".into());
-            provider.problem_submit(submit).await?;
+            let result = provider.problem_submit(submit).await;
             sp.stop();
+            stop_on_cancel(result)?;
         }
         Command::Test(test) => {
+            interrupt::catch_ctrl_c();
             let sp = Spinner::new(Spinners::Dots9, "Waiting for judge result!".into());
-            provider.problem_test(test).await?;
+            let result = provider.problem_test(test).await;
             sp.stop();
+            stop_on_cancel(result)?;
         }
     }
     Ok(())
 }
 
-fn get_config(mut config_dir: PathBuf) -> Config {
-    config_dir.push("config.json");
-    Config::get(config_dir)
+/// Move past the spinner line before reporting a cancelled judge run.
+fn stop_on_cancel(result: Result<()>) -> Result<()> {
+    if let Err(LeetUpError::Cancelled) = result {
+        interrupt::reset_terminal();
+    }
+    result
+}
+
+/// Log errors only unless `RUST_LOG` is set or `-v` is given.
+fn init_logger(verbose: u8) {
+    let filter = match verbose {
+        0 => "error",
+        1 => "leetup=info",
+        2 => "leetup=debug",
+        _ => "debug",
+    };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(filter)).init();
+}
+
+/// Load the config from `$LEETUP_CONFIG`, `~/.config/leetup/config.toml`
+/// or the legacy `~/.leetup/config.json`, in that order.
+fn get_config(config_dir: &Path) -> Result<Config> {
+    Config::get(config_path(config_dir))
+}
+
+/// Path of the config file `get_config` reads, which may not exist.
+fn config_path(config_dir: &Path) -> PathBuf {
+    if let Some(path) = env::var_os(CONFIG_ENV) {
+        return PathBuf::from(path);
+    }
+
+    dirs::home_dir()
+        .map(|home| home.join(".config").join("leetup").join("config.toml"))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| config_dir.join("config.json"))
 }
 
 fn get_session(cache: &mut KvStore) -> Result<Option<Session>> {
@@ -241,7 +1339,7 @@ fn get_session(cache: &mut KvStore) -> Result<Option<Session>> {
     Ok(session)
 }
 
-fn create_config_directory() -> Result<PathBuf> {
+pub(crate) fn create_config_directory() -> Result<PathBuf> {
     // create .leetup directory: ~/.leetup/*.log
     let mut data_dir = PathBuf::new();
     data_dir.push(
@@ -253,3 +1351,320 @@ fn create_config_directory() -> Result<PathBuf> {
 
     Ok(data_dir)
 }
+
+#[test]
+fn test_query_acceptance() {
+    let queries = Query::from_str("Ea>50.5La<70").unwrap();
+    assert_eq!(
+        queries,
+        vec![
+            Query::NotEasy,
+            Query::AcceptanceAbove(50.5),
+            Query::Unlocked,
+            Query::AcceptanceBelow(70.0),
+        ]
+    );
+    assert!(Query::from_str("a50").is_err());
+    assert!(Query::from_str("a>").is_err());
+}
+
+#[test]
+fn test_order_by() {
+    assert_eq!(
+        OrderBy::from_str("iFa").unwrap(),
+        vec![
+            OrderBy::IdAsc,
+            OrderBy::FrequencyDesc,
+            OrderBy::AcceptanceAsc
+        ]
+    );
+    assert_eq!(
+        OrderBy::from_str("Nd").unwrap(),
+        vec![OrderBy::NewDesc, OrderBy::DifficultyAsc]
+    );
+    let err = OrderBy::from_str("ix").unwrap_err().to_string();
+    assert!(err.contains("Unknown order `x`"));
+    // A bare `id` is still id then difficulty
+    assert_eq!(
+        OrderBy::from_str("id").unwrap(),
+        vec![OrderBy::IdAsc, OrderBy::DifficultyAsc]
+    );
+}
+
+#[test]
+fn test_order_by_tokens() {
+    assert_eq!(
+        OrderBy::from_str("id,difficulty-desc").unwrap(),
+        vec![OrderBy::IdAsc, OrderBy::DifficultyDesc]
+    );
+    assert_eq!(
+        OrderBy::from_str("difficulty-desc, acceptance-asc,title").unwrap(),
+        vec![
+            OrderBy::DifficultyDesc,
+            OrderBy::AcceptanceAsc,
+            OrderBy::TitleAsc
+        ]
+    );
+    // Short and long keys mix
+    assert_eq!(
+        OrderBy::from_str("new-desc,Fa").unwrap(),
+        vec![
+            OrderBy::NewDesc,
+            OrderBy::FrequencyDesc,
+            OrderBy::AcceptanceAsc
+        ]
+    );
+    assert_eq!(
+        OrderBy::from_str("d,I").unwrap(),
+        vec![OrderBy::DifficultyAsc, OrderBy::IdDesc]
+    );
+    assert_eq!(
+        OrderBy::from_str("acceptance-desc").unwrap(),
+        vec![OrderBy::AcceptanceDesc]
+    );
+    assert_eq!(OrderBy::from_str("id-asc").unwrap(), vec![OrderBy::IdAsc]);
+
+    let err = |order| OrderBy::from_str(order).unwrap_err().to_string();
+    assert!(err("Nn").contains("`new` is given more than once"));
+    assert!(err("id,I").contains("`id` is given more than once"));
+    assert!(err("title,title-desc").contains("`title` is given more than once"));
+    assert!(err("id,,title").contains("Empty order key"));
+    assert!(err("difficulty-down").contains("Unknown order `difficulty-down`"));
+}
+
+#[test]
+fn test_query_unknown() {
+    assert_eq!(
+        Query::from_str("eL").unwrap(),
+        vec![Query::Easy, Query::Unlocked]
+    );
+    let err = Query::from_str("eX").unwrap_err().to_string();
+    assert!(err.contains("Unknown query `X`"));
+}
+
+#[test]
+fn test_query_conflicts() {
+    let check = |q: &str| Query::check_conflicts(&Query::from_str(q).unwrap());
+    assert!(check("eLD").is_ok());
+    assert!(check("a>30a<60").is_ok());
+    assert!(check("EM").is_ok());
+
+    for q in ["em", "eE", "lL", "dD", "sS", "EMH", "a>60a<30"] {
+        assert!(
+            matches!(check(q), Err(LeetUpError::ConflictingQuery(..))),
+            "{}",
+            q
+        );
+    }
+    assert!(check("hm").unwrap_err().to_string().contains("`m` and `h`"));
+}
+
+#[test]
+fn test_list_query_flags() {
+    let query = |args: &[&str]| {
+        List::from_iter_safe(["list"].iter().chain(args))
+            .unwrap()
+            .query()
+    };
+    assert_eq!(query(&[]), None);
+    assert_eq!(query(&["-q", "e"]), Some("e".into()));
+    assert_eq!(query(&["--solved", "--free"]), Some("dL".into()));
+    assert_eq!(
+        query(&["-q", "a>50", "--unsolved", "--paid"]),
+        Some("a>50Dl".into())
+    );
+    assert!(List::from_iter_safe(&["list", "--solved", "--unsolved"]).is_err());
+
+    let filters_status = |args: &[&str]| {
+        List::from_iter_safe(["list"].iter().chain(args))
+            .unwrap()
+            .filters_status()
+            .unwrap()
+    };
+    assert!(!filters_status(&[]));
+    assert!(!filters_status(&["-q", "eL"]));
+    assert!(filters_status(&["--solved"]));
+    assert!(filters_status(&["-q", "mD"]));
+    assert!(List::from_iter_safe(&["list", "--free", "--paid"]).is_err());
+}
+
+#[test]
+fn test_list_with_profile() {
+    let profile = FilterProfile {
+        query: Some("mD".into()),
+        tags: vec!["array".into()],
+        order: Some("a".into()),
+    };
+    let list = |args: &[&str]| {
+        List::from_iter_safe(["list", "--profile", "interview"].iter().chain(args))
+            .unwrap()
+            .with_profile(&profile)
+    };
+    let profiled = list(&[]);
+    assert_eq!(profiled.query(), Some("mD".into()));
+    assert_eq!(profiled.tag, ["array"]);
+    assert_eq!(profiled.order.as_deref(), Some("a"));
+
+    let overridden = list(&["-o", "i", "-t", "string", "--free"]);
+    assert_eq!(overridden.query(), Some("mDL".into()));
+    assert_eq!(overridden.tag, ["string"]);
+    assert_eq!(overridden.order.as_deref(), Some("i"));
+    assert_eq!(list(&["-q", "h"]).query(), Some("h".into()));
+}
+
+#[test]
+fn test_list_only_ids() {
+    assert!(
+        List::from_iter_safe(&["list", "-q", "hD", "-n", "5", "--only-ids"])
+            .unwrap()
+            .only_ids
+    );
+    assert!(List::from_iter_safe(&["list", "--only-ids", "--count"]).is_err());
+    assert!(List::from_iter_safe(&["list", "--only-ids", "--format", "{id}"]).is_err());
+}
+
+#[test]
+fn test_list_no_cache() {
+    assert!(
+        List::from_iter_safe(&["list", "--no-cache"])
+            .unwrap()
+            .refresh
+    );
+}
+
+#[test]
+fn test_list_categories() {
+    let categories = |args: &[&str]| {
+        List::from_iter_safe(["list"].iter().chain(args))
+            .unwrap()
+            .categories()
+    };
+    assert_eq!(categories(&[]), None);
+    assert_eq!(
+        categories(&["--category", "database"]),
+        Some(vec![Category::Database])
+    );
+    assert_eq!(
+        categories(&["--all-categories"]),
+        Some(Category::ALL.to_vec())
+    );
+    assert!(List::from_iter_safe(&["list", "--category", "sql"]).is_err());
+    assert!(List::from_iter_safe(&["list", "--category", "shell", "--all-categories"]).is_err());
+    assert!(List::from_iter_safe(&["list", "--all-categories", "-t", "array"]).is_err());
+}
+
+#[test]
+fn test_id_range() {
+    let range = |s: &str| IdRange::from_str(s);
+    assert_eq!(
+        range("1-100").unwrap(),
+        IdRange {
+            start: 1,
+            end: Some(100)
+        }
+    );
+    assert_eq!(
+        range(" 200 - ").unwrap(),
+        IdRange {
+            start: 200,
+            end: None
+        }
+    );
+    for s in ["", "5", "-5", "a-b", "1-2-3", "1--", "10-1"] {
+        assert!(range(s).is_err(), "{}", s);
+    }
+
+    let range = range("5-7").unwrap();
+    assert!(!range.contains(4));
+    assert!(range.contains(5) && range.contains(7));
+    assert!(!range.contains(8));
+    assert!(List::from_iter_safe(&["list", "--range", "x"]).is_err());
+}
+
+#[test]
+fn test_stats_bucket_width() {
+    let width = |args: &[&str]| {
+        Stats::from_iter_safe(["stats"].iter().chain(args)).map(|stats| stats.bucket_width)
+    };
+    assert_eq!(width(&[]).unwrap(), 20);
+    assert_eq!(width(&["--bucket-width", "25"]).unwrap(), 25);
+    for invalid in ["0", "101", "ten"] {
+        assert!(width(&["--bucket-width", invalid]).is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn test_stats_weeks() {
+    let weeks =
+        |args: &[&str]| Stats::from_iter_safe(["stats"].iter().chain(args)).map(|s| s.weeks);
+    assert_eq!(weeks(&[]).unwrap(), 12);
+    assert_eq!(weeks(&["--weeks", "520"]).unwrap(), 520);
+    assert!(weeks(&["--weeks", "521"]).is_err());
+    assert!(weeks(&["--weeks", "100000000000"]).is_err());
+}
+
+#[test]
+fn test_content_lang() {
+    let show = |args: &[&str]| Show::from_iter_safe(["show", "1"].iter().chain(args));
+    assert_eq!(show(&[]).unwrap().content_lang, ContentLang::En);
+    assert_eq!(
+        show(&["--content-lang", "zh"]).unwrap().content_lang,
+        ContentLang::Zh
+    );
+    assert!(show(&["--content-lang", "fr"]).is_err());
+}
+
+#[test]
+fn test_submit_stdin() {
+    let submit = |args: &[&str]| Submit::from_iter_safe(["submit"].iter().chain(args));
+    assert!(submit(&["two-sum.rs"]).is_ok());
+    assert!(
+        submit(&["--stdin", "--lang", "rust", "--id", "1"])
+            .unwrap()
+            .stdin
+    );
+    assert!(submit(&[]).unwrap().filename.is_none());
+    assert!(submit(&["--id", "1"]).is_ok());
+    assert!(submit(&["two-sum.rs", "--id", "1"]).is_err());
+    assert!(submit(&["--stdin", "--lang", "rust"]).is_err());
+    assert!(submit(&["two-sum.rs", "--stdin", "--lang", "rust", "--id", "1"]).is_err());
+}
+
+#[test]
+fn test_completions() {
+    let shell = |name: &str| {
+        LeetUpArgs::from_iter_safe(&["leetup", "completions", name]).map(|args| {
+            match args.command {
+                Command::Completions(completions) => completions.shell,
+                _ => unreachable!(),
+            }
+        })
+    };
+    assert!(matches!(shell("bash"), Ok(Shell::Bash)));
+    assert!(matches!(shell("PowerShell"), Ok(Shell::PowerShell)));
+    let err = shell("tcsh").unwrap_err().to_string();
+    assert!(err.contains("zsh") && err.contains("fish"), "{}", err);
+
+    let mut script = vec![];
+    LeetUpArgs::clap().gen_completions_to("leetup", Shell::Bash, &mut script);
+    assert!(String::from_utf8(script).unwrap().contains("submit"));
+}
+
+#[test]
+fn test_test_edit() {
+    let test = |args: &[&str]| Test::from_iter_safe(["test", "two-sum.rs"].iter().chain(args));
+    assert!(test(&["--edit"]).unwrap().edit);
+    assert!(test(&["--edit", "-t", "[1]"]).is_err());
+}
+
+#[test]
+fn test_star_query() {
+    let star = |args: &[&str]| Star::from_iter_safe(["star"].iter().chain(args));
+    assert_eq!(star(&["two-sum"]).unwrap().id.as_deref(), Some("two-sum"));
+    let bulk = star(&["-q", "HD", "--yes"]).unwrap();
+    assert_eq!(bulk.query.as_deref(), Some("HD"));
+    assert!(bulk.id.is_none() && bulk.yes);
+    assert!(star(&[]).is_err());
+    assert!(star(&["two-sum", "-q", "HD"]).is_err());
+    assert!(star(&["two-sum", "--yes"]).is_err());
+}
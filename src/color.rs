@@ -0,0 +1,184 @@
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use ansi_term::Colour::{self, *};
+
+use crate::config::ColorNames;
+
+/// Whether output should be colored, decided once at startup.
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Colors picked in the config, set once at startup.
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Disable colors if `--no-color` is passed or `NO_COLOR` is set.
+///
+/// See https://no-color.org
+pub fn init(no_color: bool) {
+    let no_color_env = env::var_os("NO_COLOR").is_some_and(|val| !val.is_empty());
+    COLOR_ENABLED.store(!no_color && !no_color_env, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    COLOR_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Colors of the difficulty levels and of the list icons.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub easy: Colour,
+    pub medium: Colour,
+    pub hard: Colour,
+    pub starred: Colour,
+    pub locked: Colour,
+    pub done: Colour,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            easy: Green,
+            medium: Yellow,
+            hard: Red,
+            starred: Yellow,
+            locked: Red,
+            done: Green,
+        }
+    }
+}
+
+impl Theme {
+    /// Theme with the colors named in the config, keeping the default of
+    /// any color that is missing or can't be parsed, with a warning.
+    pub fn new(names: &ColorNames) -> Self {
+        let mut theme = Theme::default();
+        let colors = [
+            ("easy", &names.easy, &mut theme.easy),
+            ("medium", &names.medium, &mut theme.medium),
+            ("hard", &names.hard, &mut theme.hard),
+            ("starred", &names.starred, &mut theme.starred),
+            ("locked", &names.locked, &mut theme.locked),
+            ("done", &names.done, &mut theme.done),
+        ];
+        for (key, name, colour) in colors {
+            let Some(name) = name else { continue };
+            match parse_colour(name) {
+                Some(parsed) => *colour = parsed,
+                None => eprintln!(
+                    "Warning: unknown color `{}` for `colors.{}`, using the default. \
+                     Use a name such as red or bright-blue, or a 256-color number",
+                    name, key
+                ),
+            }
+        }
+        theme
+    }
+}
+
+/// Use `theme` instead of the default colors. Only the first call counts.
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// Colors set with `set_theme`, the default ones otherwise.
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+/// Parse a color name such as `red` or `bright-blue`, or a 256-color
+/// palette number.
+pub fn parse_colour(name: &str) -> Option<Colour> {
+    let name = name.trim().to_lowercase();
+    if let Ok(index) = name.parse::<u8>() {
+        return Some(Fixed(index));
+    }
+    let (bright, base) = match name.strip_prefix("bright-") {
+        Some(base) => (true, base),
+        None => (false, name.as_str()),
+    };
+    let index = match base {
+        "black" => 0,
+        "red" => 1,
+        "green" => 2,
+        "yellow" => 3,
+        "blue" => 4,
+        "magenta" | "purple" => 5,
+        "cyan" => 6,
+        "white" => 7,
+        _ => return None,
+    };
+    Some(match (bright, index) {
+        (true, index) => Fixed(index + 8),
+        (false, 0) => Black,
+        (false, 1) => Red,
+        (false, 2) => Green,
+        (false, 3) => Yellow,
+        (false, 4) => Blue,
+        (false, 5) => Purple,
+        (false, 6) => Cyan,
+        _ => White,
+    })
+}
+
+/// Paint text using `ansi_term`, leave it plain if colors are disabled.
+pub fn paint(colour: Colour, text: &str) -> String {
+    if is_enabled() {
+        colour.paint(text).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Same as `colci::Color` but respects `--no-color` and `NO_COLOR`.
+pub enum Color<'a> {
+    Cyan(&'a str),
+    Green(&'a str),
+    Red(&'a str),
+    Yellow(&'a str),
+    Magenta(&'a str),
+}
+
+impl Color<'_> {
+    /// Make decorated strings
+    pub fn make(self) -> String {
+        let (color, text) = match self {
+            Color::Cyan(s) => (colci::Color::Cyan(s), s),
+            Color::Green(s) => (colci::Color::Green(s), s),
+            Color::Red(s) => (colci::Color::Red(s), s),
+            Color::Yellow(s) => (colci::Color::Yellow(s), s),
+            Color::Magenta(s) => (colci::Color::Magenta(s), s),
+        };
+
+        if is_enabled() {
+            color.make()
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[test]
+fn test_theme() {
+    assert_eq!(parse_colour("Blue"), Some(Blue));
+    assert_eq!(parse_colour("magenta"), Some(Purple));
+    assert_eq!(parse_colour("bright-red"), Some(Fixed(9)));
+    assert_eq!(parse_colour("208"), Some(Fixed(208)));
+    assert_eq!(parse_colour("256"), None);
+    assert_eq!(parse_colour("orange"), None);
+
+    let names = ColorNames {
+        easy: Some("blue".into()),
+        hard: Some("orange".into()),
+        done: Some("33".into()),
+        ..ColorNames::default()
+    };
+    assert_eq!(
+        Theme::new(&names),
+        Theme {
+            easy: Blue,
+            done: Fixed(33),
+            ..Theme::default()
+        }
+    );
+}
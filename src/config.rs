@@ -1,69 +1,341 @@
+use std::fmt;
 use std::fs::File;
-use std::io::Read;
+use std::io::{self, Read};
 use std::path::Path;
+use std::time::Duration;
 use std::{collections::HashMap, str::FromStr};
 
-use log::warn;
-use serde::{de::DeserializeOwned, Deserialize};
+use anyhow::anyhow;
+use log::debug;
+use serde::{
+    de::{self, DeserializeOwned},
+    Deserialize,
+};
 
-use crate::{service::Lang, LeetUpError, Result};
+use crate::{
+    service::{judge::PollSettings, Lang},
+    LeetUpError, Result,
+};
+
+/// Refresh problems cache once a day by default.
+const DEFAULT_CACHE_TTL: u64 = 24 * 60 * 60;
+
+/// Give up on a request after 30 seconds by default.
+const DEFAULT_REQUEST_TIMEOUT: u64 = 30;
+
+/// Try transient failures 3 times by default.
+const DEFAULT_REQUEST_ATTEMPTS: u32 = 3;
+
+/// Ask the judge for a verdict every second by default.
+const DEFAULT_POLL_INTERVAL: f64 = 1.0;
+
+/// Wait 30 seconds for a verdict by default.
+const DEFAULT_POLL_TIMEOUT: u64 = 30;
+
+/// Check for a new daily challenge every 15 minutes by default.
+const DEFAULT_DAILY_WATCH_INTERVAL: u64 = 15;
+
+/// Environment variables overriding the `base_url`, `api_url` and
+/// `problems_all_url` config keys.
+pub const BASE_URL_ENV: &str = "LEETUP_BASE_URL";
+pub const API_URL_ENV: &str = "LEETUP_API_URL";
+pub const PROBLEMS_ALL_URL_ENV: &str = "LEETUP_PROBLEMS_ALL_URL";
 
 type LangInjectCode = HashMap<String, InjectCode>;
 type PickHookConfig = HashMap<String, PickHook>;
 
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 pub struct Config {
     #[serde(skip)]
     pub urls: Urls,
     pub inject_code: Option<LangInjectCode>,
     pub pick_hook: Option<PickHookConfig>,
     pub lang: Lang,
+
+    /// Number of seconds the problems cache stays fresh.
+    pub cache_ttl: Option<u64>,
+
+    /// Site to use, leetcode-us by default.
+    pub site: Option<Site>,
+
+    /// Root of a LeetCode mirror or proxy, replaces the site's.
+    pub base_url: Option<String>,
+
+    /// API root, `{base_url}/api` by default.
+    pub api_url: Option<String>,
+
+    /// Problem list endpoint, `{api_url}/problems/all` by default.
+    pub problems_all_url: Option<String>,
+
+    /// Number of seconds before a request times out.
+    pub request_timeout: Option<u64>,
+
+    /// Number of attempts for requests failing with a timeout or 5xx.
+    pub request_attempts: Option<u32>,
+
+    /// Seconds between two judge result polls, may be fractional.
+    pub poll_interval: Option<f64>,
+
+    /// Seconds to wait for a judge result before giving up.
+    pub poll_timeout: Option<u64>,
+
+    /// Minutes between two checks of `daily --watch`.
+    pub daily_watch_interval: Option<u64>,
+
+    /// Send a desktop notification when `daily --watch` sees a new challenge.
+    pub daily_notify: bool,
+
+    /// Wait for `Retry-After` and retry once when rate limited.
+    pub retry_rate_limited: bool,
+
+    /// Colored output, enabled by default.
+    pub color: Option<bool>,
+
+    /// Open picked problems with `$EDITOR`, like `pick --open-editor`.
+    pub open_editor: bool,
+
+    /// Path of picked problems relative to the working directory,
+    /// `{slug}.{ext}` by default.
+    pub filename_template: Option<String>,
+
+    /// Directory of the `.testlog` files recording failed `test` runs,
+    /// next to the solution file by default.
+    pub test_log_dir: Option<String>,
+
+    /// Points per solved problem in the `stats` score.
+    pub score_weights: ScoreWeights,
+
+    /// Colors replacing the default difficulty and icon ones.
+    pub colors: ColorNames,
+
+    /// Named `list` filters, used with `list --profile <name>`.
+    pub profiles: HashMap<String, FilterProfile>,
+
+    /// Only use cached data, set by `--offline`.
+    #[serde(skip)]
+    pub offline: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            urls: Urls::default(),
+            inject_code: None,
+            pick_hook: None,
+            lang: Lang::from_str("rust").unwrap(),
+            cache_ttl: None,
+            site: None,
+            base_url: None,
+            api_url: None,
+            problems_all_url: None,
+            request_timeout: None,
+            request_attempts: None,
+            poll_interval: None,
+            poll_timeout: None,
+            daily_watch_interval: None,
+            daily_notify: false,
+            open_editor: false,
+            filename_template: None,
+            test_log_dir: None,
+            score_weights: ScoreWeights::default(),
+            colors: ColorNames::default(),
+            profiles: HashMap::new(),
+            retry_rate_limited: false,
+            color: None,
+            offline: false,
+        }
+    }
 }
 
 impl Config {
-    pub fn get<P: AsRef<Path>>(path: P) -> Self {
-        let base = "https://leetcode.com";
-        let urls = Urls {
-            base: base.to_owned(),
-            api: format!("{}/api", base),
-            graphql: format!("{}/graphql", base),
-            problems: format!("{}/problems/", base),
-            problems_all: format!("{}/api/problems/all", base),
-            github_login: format!("{}/accounts/github/login/?next=%2F", base),
-            github_login_request: "https://github.com/login".to_string(),
-            github_session_request: "https://github.com/session".to_string(),
-            test: format!("{}/problems/$slug/interpret_solution/", base),
-            submit: format!("{}/problems/$slug/submit/", base),
-            submissions: format!("{}/api/submissions/$slug", base),
-            submission: format!("{}/submissions/detail/$id", base),
-            verify: format!("{}/submissions/detail/$id/check/", base),
+    /// Load the config file at `path`, TOML if it ends with `.toml` and JSON otherwise.
+    ///
+    /// A missing file yields the defaults, a malformed one is an error.
+    pub fn get<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut config = match Config::get_config(path) {
+            Ok(c) => c,
+            Err(LeetUpError::Io(e)) if e.kind() == io::ErrorKind::NotFound => {
+                debug!("No config at {}, using defaults", path.display());
+                Config::default()
+            }
+            Err(e) => {
+                return Err(LeetUpError::InvalidConfig(
+                    path.display().to_string(),
+                    e.to_string(),
+                ))
+            }
         };
+        config.override_urls(|name| std::env::var(name).ok());
+        config.set_site(config.site.unwrap_or_default());
+        config
+            .check_urls()
+            .map_err(|e| LeetUpError::InvalidConfig(path.display().to_string(), e))?;
 
-        let config: Result<Config> = Config::get_config(path);
+        Ok(config)
+    }
 
-        match config {
-            Ok(mut c) => {
-                c.urls = urls.clone();
-                c
+    /// Point all urls to the given site, unless overridden by `base_url`,
+    /// `api_url` or `problems_all_url`.
+    pub fn set_site(&mut self, site: Site) {
+        self.site = Some(site);
+        let base = self
+            .base_url
+            .as_deref()
+            .unwrap_or(site.base_url())
+            .trim_end_matches('/');
+        let api = match self.api_url {
+            Some(ref api) => api.trim_end_matches('/').to_string(),
+            None => format!("{}/api", base),
+        };
+        self.urls = Urls::with_api(base, &api);
+        if let Some(ref problems_all) = self.problems_all_url {
+            self.urls.problems_all = problems_all.to_owned();
+        }
+    }
+
+    /// Take URL overrides from the environment, `var` looks a variable up.
+    fn override_urls(&mut self, var: impl Fn(&str) -> Option<String>) {
+        let overrides = [
+            (BASE_URL_ENV, &mut self.base_url),
+            (API_URL_ENV, &mut self.api_url),
+            (PROBLEMS_ALL_URL_ENV, &mut self.problems_all_url),
+        ];
+        for (name, url) in overrides {
+            if let Some(value) = var(name).filter(|value| !value.is_empty()) {
+                *url = Some(value);
             }
-            Err(e) => {
-                warn!("{:#?}", e);
-                Config {
-                    urls,
-                    inject_code: None,
-                    pick_hook: None,
-                    lang: Lang::from_str("rust").unwrap(),
-                }
+        }
+    }
+
+    /// Make sure the overridable URLs parse and use the same http(s) scheme.
+    fn check_urls(&self) -> std::result::Result<(), String> {
+        let urls = [
+            ("base_url", &self.urls.base),
+            ("api_url", &self.urls.api),
+            ("problems_all_url", &self.urls.problems_all),
+        ];
+        let mut schemes = vec![];
+        for (name, value) in urls {
+            // The URL parser drops tabs and newlines and accepts IDN hosts,
+            // but the raw value is sent as is, e.g. in the Origin header.
+            if !value.bytes().all(|b| b.is_ascii_graphic()) {
+                return Err(format!(
+                    "`{}` must be plain ASCII without spaces, use punycode for \
+                     international domains: {:?}",
+                    name, value
+                ));
+            }
+            let url = url::Url::parse(value)
+                .map_err(|e| format!("`{}` is not a valid URL ({}): {}", name, value, e))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(format!("`{}` must be an http(s) URL: {}", name, value));
             }
+            schemes.push((name, url.scheme().to_string()));
+        }
+        if let Some((name, scheme)) = schemes.iter().find(|(_, s)| *s != schemes[0].1) {
+            return Err(format!(
+                "`{}` uses {} but `{}` uses {}, use the same scheme for both",
+                name, scheme, schemes[0].0, schemes[0].1
+            ));
         }
+
+        Ok(())
+    }
+
+    /// Number of seconds the problems cache stays fresh.
+    pub fn cache_ttl(&self) -> u64 {
+        self.cache_ttl.unwrap_or(DEFAULT_CACHE_TTL)
+    }
+
+    /// Time allowed for a single request.
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout.unwrap_or(DEFAULT_REQUEST_TIMEOUT))
+    }
+
+    /// Number of attempts for a request, at least one.
+    pub fn request_attempts(&self) -> u32 {
+        self.request_attempts
+            .unwrap_or(DEFAULT_REQUEST_ATTEMPTS)
+            .max(1)
     }
 
-    fn get_config<P: AsRef<Path>, T: DeserializeOwned>(path: P) -> Result<T> {
+    /// Judge polling, `interval` and `timeout` in seconds overriding the
+    /// config when given.
+    pub fn poll_settings(
+        &self,
+        interval: Option<f64>,
+        timeout: Option<u64>,
+    ) -> Result<PollSettings> {
+        let interval = interval
+            .or(self.poll_interval)
+            .unwrap_or(DEFAULT_POLL_INTERVAL);
+        let interval = match Duration::try_from_secs_f64(interval) {
+            Ok(interval) if interval > Duration::ZERO => interval,
+            _ => {
+                return Err(LeetUpError::Any(anyhow!(
+                    "Poll interval must be a positive number of seconds, got {}",
+                    interval
+                )))
+            }
+        };
+        let timeout = timeout
+            .or(self.poll_timeout)
+            .unwrap_or(DEFAULT_POLL_TIMEOUT);
+
+        Ok(PollSettings {
+            interval,
+            timeout: Duration::from_secs(timeout),
+        })
+    }
+
+    /// Filter profile called `name`, listing the defined ones if unknown.
+    pub fn profile(&self, name: &str) -> Result<&FilterProfile> {
+        self.profiles.get(name).ok_or_else(|| {
+            let mut names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            names.sort_unstable();
+            let available = if names.is_empty() {
+                "none are defined in the config".to_string()
+            } else {
+                format!("expected one of: {}", names.join(", "))
+            };
+            LeetUpError::Any(anyhow!("Unknown profile `{}`, {}", name, available))
+        })
+    }
+
+    /// Time between two checks of `daily --watch`, `minutes` overriding
+    /// the config when given.
+    pub fn daily_watch_interval(&self, minutes: Option<u64>) -> Result<Duration> {
+        match minutes
+            .or(self.daily_watch_interval)
+            .unwrap_or(DEFAULT_DAILY_WATCH_INTERVAL)
+        {
+            0 => Err(LeetUpError::Any(anyhow!(
+                "Daily watch interval must be at least one minute"
+            ))),
+            minutes => minutes
+                .checked_mul(60)
+                .map(Duration::from_secs)
+                .ok_or_else(|| {
+                    LeetUpError::Any(anyhow!(
+                        "Daily watch interval of {} minutes is too large",
+                        minutes
+                    ))
+                }),
+        }
+    }
+
+    fn get_config<T: DeserializeOwned>(path: &Path) -> Result<T> {
         let mut buf = String::new();
         let mut file = File::open(path)?;
         file.read_to_string(&mut buf)?;
 
-        serde_json::from_str(&buf).map_err(LeetUpError::Serde)
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&buf).map_err(|e| LeetUpError::Any(e.into()))
+        } else {
+            serde_json::from_str(&buf).map_err(LeetUpError::Serde)
+        }
     }
 }
 
@@ -85,15 +357,103 @@ impl From<String> for Either {
     }
 }
 
-impl ToString for Either {
-    fn to_string(&self) -> String {
+impl fmt::Display for Either {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Either::String(s) => write!(f, "{}", s),
+            Either::Sequence(v) => write!(f, "{}", v.join("\n")),
+        }
+    }
+}
+
+/// Points given per solved problem of each difficulty.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct ScoreWeights {
+    pub easy: usize,
+    pub medium: usize,
+    pub hard: usize,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        ScoreWeights {
+            easy: 1,
+            medium: 3,
+            hard: 5,
+        }
+    }
+}
+
+/// Preset `list` filters, overridden by the flags given on the command line.
+#[derive(Debug, Deserialize, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct FilterProfile {
+    /// Same as `list -q`.
+    pub query: Option<String>,
+
+    /// Same as `list --tag`, repeated.
+    pub tags: Vec<String>,
+
+    /// Same as `list --order`.
+    pub order: Option<String>,
+}
+
+/// Color names from the `colors` config section, see `color::Theme`.
+#[derive(Debug, Deserialize, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ColorNames {
+    pub easy: Option<String>,
+    pub medium: Option<String>,
+    pub hard: Option<String>,
+    pub starred: Option<String>,
+    pub locked: Option<String>,
+    pub done: Option<String>,
+}
+
+/// LeetCode sites supported by leetup.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Site {
+    #[default]
+    LeetcodeUs,
+    /// Same endpoints as `leetcode.com` on another host, see docs/usage.md.
+    LeetcodeCn,
+}
+
+impl Site {
+    pub fn base_url(&self) -> &'static str {
         match self {
-            Either::String(s) => s.to_owned(),
-            Either::Sequence(v) => v.join("\n"),
+            Site::LeetcodeUs => "https://leetcode.com",
+            Site::LeetcodeCn => "https://leetcode.cn",
         }
     }
 }
 
+impl FromStr for Site {
+    type Err = LeetUpError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "leetcode-us" | "us" => Ok(Site::LeetcodeUs),
+            "leetcode-cn" | "cn" => Ok(Site::LeetcodeCn),
+            _ => Err(LeetUpError::Any(anyhow!(
+                "Unknown site `{}`, expected one of: leetcode-us, leetcode-cn",
+                s
+            ))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Site {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Site::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
 #[derive(Debug, Default, Deserialize, Clone)]
 pub struct Urls {
     pub base: String,
@@ -101,6 +461,7 @@ pub struct Urls {
     pub graphql: String,
     pub problems: String,
     pub problems_all: String,
+    pub problems_category: String,
     pub github_login: String,
     pub github_login_request: String,
     pub github_session_request: String,
@@ -109,6 +470,46 @@ pub struct Urls {
     pub submissions: String,
     pub submission: String,
     pub verify: String,
+    pub favorites: String,
+    pub favorite_delete: String,
+}
+
+impl Urls {
+    pub fn new(base: &str) -> Self {
+        Urls::with_api(base, &format!("{}/api", base))
+    }
+
+    /// Urls of the site at `base` whose API lives at `api`.
+    pub fn with_api(base: &str, api: &str) -> Self {
+        Urls {
+            base: base.to_owned(),
+            api: api.to_owned(),
+            graphql: format!("{}/graphql", base),
+            problems: format!("{}/problems/", base),
+            problems_all: format!("{}/problems/all", api),
+            problems_category: format!("{}/problems/$category/", api),
+            github_login: format!("{}/accounts/github/login/?next=%2F", base),
+            github_login_request: "https://github.com/login".to_string(),
+            github_session_request: "https://github.com/session".to_string(),
+            test: format!("{}/problems/$slug/interpret_solution/", base),
+            submit: format!("{}/problems/$slug/submit/", base),
+            submissions: format!("{}/submissions/$slug", api),
+            submission: format!("{}/submissions/detail/$id", base),
+            verify: format!("{}/submissions/detail/$id/check/", base),
+            favorites: format!("{}/list/api/questions", base),
+            favorite_delete: format!("{}/list/api/questions/$hash/$id", base),
+        }
+    }
+
+    /// Problem list of `category`, e.g. `algorithms`, from the
+    /// `problems_category` template. `all` is `problems_all`, so a
+    /// `problems_all_url` override still applies.
+    pub fn problems(&self, category: &str) -> String {
+        match category {
+            "all" => self.problems_all.to_owned(),
+            category => self.problems_category.replace("$category", category),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -171,13 +572,187 @@ fn test_config() {
     let file_path = data_dir.path().join("config.json");
 
     let mut file = std::fs::File::create(&file_path).unwrap();
-    file.write(data.to_string().as_bytes()).unwrap();
+    file.write_all(data.to_string().as_bytes()).unwrap();
 
-    let config: Config = Config::get(&file_path);
+    let config: Config = Config::get(&file_path).unwrap();
     assert!(config.inject_code.is_some());
     assert!(!config.urls.base.is_empty());
     assert!(config.pick_hook.is_some());
     assert!(matches!(config.lang, Lang::Java(..)));
+    assert_eq!(config.urls.base, Site::LeetcodeUs.base_url());
+    assert_eq!(config.request_timeout(), Duration::from_secs(30));
+    assert_eq!(config.request_attempts(), 3);
+    drop(file);
+    data_dir.close().unwrap();
+}
+
+#[test]
+fn test_config_site() {
+    use std::io::Write;
+
+    let data_dir = tempfile::tempdir().unwrap();
+    let file_path = data_dir.path().join("config.json");
+    let mut file = std::fs::File::create(&file_path).unwrap();
+    file.write_all(br#"{"lang": "rust", "site": "leetcode-cn"}"#)
+        .unwrap();
+
+    let mut config: Config = Config::get(&file_path).unwrap();
+    assert_eq!(config.site, Some(Site::LeetcodeCn));
+    assert_eq!(config.urls.graphql, "https://leetcode.cn/graphql");
+
+    config.set_site(Site::LeetcodeUs);
+    assert_eq!(
+        config.urls.problems_all,
+        "https://leetcode.com/api/problems/all"
+    );
+    drop(file);
+    data_dir.close().unwrap();
+}
+
+#[test]
+fn test_config_url_overrides() {
+    let mut config = Config {
+        base_url: Some("http://mirror.local/".into()),
+        ..Config::default()
+    };
+    config.override_urls(|name| match name {
+        API_URL_ENV => Some("http://mirror.local/v1".into()),
+        BASE_URL_ENV => Some(String::new()),
+        _ => None,
+    });
+    config.set_site(Site::LeetcodeCn);
+    assert_eq!(config.urls.base, "http://mirror.local");
+    assert_eq!(config.urls.graphql, "http://mirror.local/graphql");
+    assert_eq!(
+        config.urls.problems_all,
+        "http://mirror.local/v1/problems/all"
+    );
+    assert_eq!(config.check_urls(), Ok(()));
+
+    config.problems_all_url = Some("https://mirror.local/all".into());
+    config.set_site(Site::LeetcodeUs);
+    assert_eq!(config.urls.problems_all, "https://mirror.local/all");
+    assert_eq!(config.urls.problems("all"), "https://mirror.local/all");
+    assert_eq!(
+        config.urls.problems("shell"),
+        "http://mirror.local/v1/problems/shell/"
+    );
+    assert!(config.check_urls().unwrap_err().contains("same scheme"));
+
+    config.problems_all_url = Some("mirror.local/all".into());
+    config.set_site(Site::LeetcodeUs);
+    assert!(config.check_urls().unwrap_err().contains("not a valid URL"));
+
+    config.problems_all_url = Some("ftp://mirror.local/all".into());
+    config.set_site(Site::LeetcodeUs);
+    assert!(config.check_urls().unwrap_err().contains("http(s)"));
+
+    config.problems_all_url = None;
+    config.base_url = Some("https://bücher.example".into());
+    config.set_site(Site::LeetcodeUs);
+    assert!(config.check_urls().unwrap_err().contains("punycode"));
+    config.base_url = Some("https://leetcode.com\n".into());
+    config.set_site(Site::LeetcodeUs);
+    assert!(config.check_urls().unwrap_err().contains("`base_url`"));
+}
+
+#[test]
+fn test_profiles() {
+    let mut config: Config = serde_json::from_str(
+        r#"{ "profiles": { "interview": { "query": "mD", "tags": ["array"], "order": "a" } } }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.profile("interview").unwrap(),
+        &FilterProfile {
+            query: Some("mD".into()),
+            tags: vec!["array".into()],
+            order: Some("a".into())
+        }
+    );
+    let err = config.profile("daily").unwrap_err().to_string();
+    assert!(err.contains("expected one of: interview"), "{}", err);
+
+    config.profiles.clear();
+    let err = config.profile("daily").unwrap_err().to_string();
+    assert!(err.contains("none are defined"), "{}", err);
+}
+
+#[test]
+fn test_poll_settings() {
+    let mut config = Config::default();
+    assert_eq!(
+        config.poll_settings(None, None).unwrap(),
+        PollSettings {
+            interval: Duration::from_secs(1),
+            timeout: Duration::from_secs(30)
+        }
+    );
+
+    config.poll_interval = Some(0.5);
+    config.poll_timeout = Some(120);
+    assert_eq!(
+        config.poll_settings(None, Some(10)).unwrap(),
+        PollSettings {
+            interval: Duration::from_millis(500),
+            timeout: Duration::from_secs(10)
+        }
+    );
+    assert!(config.poll_settings(Some(0.0), None).is_err());
+    assert!(config.poll_settings(Some(f64::NAN), None).is_err());
+    assert!(config.poll_settings(Some(1e30), None).is_err());
+
+    assert_eq!(
+        config.daily_watch_interval(None).unwrap(),
+        Duration::from_secs(15 * 60)
+    );
+    config.daily_watch_interval = Some(60);
+    assert_eq!(
+        config.daily_watch_interval(Some(5)).unwrap(),
+        Duration::from_secs(5 * 60)
+    );
+    assert!(config.daily_watch_interval(Some(0)).is_err());
+    assert!(config
+        .daily_watch_interval(Some(400_000_000_000_000_000))
+        .is_err());
+}
+
+#[test]
+fn test_config_toml() {
+    use std::io::Write;
+
+    let data_dir = tempfile::tempdir().unwrap();
+    let file_path = data_dir.path().join("config.toml");
+    let mut file = std::fs::File::create(&file_path).unwrap();
+    file.write_all(
+        b"lang = \"python3\"\nsite = \"leetcode-cn\"\ncache_ttl = 60\ncolor = false\n\
+          [score_weights]\nhard = 10\n[colors]\neasy = \"blue\"\n",
+    )
+    .unwrap();
+
+    let config = Config::get(&file_path).unwrap();
+    assert_eq!(
+        config.score_weights,
+        ScoreWeights {
+            easy: 1,
+            medium: 3,
+            hard: 10
+        }
+    );
+    assert!(matches!(config.lang, Lang::Python3(..)));
+    assert_eq!(config.site, Some(Site::LeetcodeCn));
+    assert_eq!(config.cache_ttl(), 60);
+    assert_eq!(config.color, Some(false));
+    assert_eq!(config.colors.easy.as_deref(), Some("blue"));
+    assert_eq!(config.colors.hard, None);
+
+    let config = Config::get(data_dir.path().join("missing.toml")).unwrap();
+    assert!(matches!(config.lang, Lang::Rust(..)));
+    assert_eq!(config.site, Some(Site::LeetcodeUs));
+
+    file.write_all(b"lang = [").unwrap();
+    let err = Config::get(&file_path).unwrap_err();
+    assert!(matches!(err, LeetUpError::InvalidConfig(..)), "{:?}", err);
     drop(file);
     data_dir.close().unwrap();
 }
@@ -0,0 +1,49 @@
+use ansi_term::Colour;
+use similar::TextDiff;
+
+use crate::color::paint;
+
+/// Number of unchanged lines shown around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// Unified diff from `old` to `new`, colored like `git diff`.
+///
+/// Empty if both are the same.
+pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    let diff = TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(CONTEXT_LINES)
+        .header(old_name, new_name)
+        .to_string();
+
+    let mut out = String::new();
+    for line in diff.lines() {
+        let line = if line.starts_with("+++") || line.starts_with("---") {
+            line.to_string()
+        } else if line.starts_with("@@") {
+            paint(Colour::Cyan, line)
+        } else if line.starts_with('+') {
+            paint(Colour::Green, line)
+        } else if line.starts_with('-') {
+            paint(Colour::Red, line)
+        } else {
+            line.to_string()
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    out
+}
+
+#[test]
+fn test_unified_diff() {
+    assert_eq!(unified_diff("a\nb\n", "a\nb\n", "old", "new"), "");
+
+    let diff = unified_diff("a\nb\nc\n", "a\nB\nc\n", "accepted", "local");
+    let diff = strip_ansi_escapes::strip_str(diff);
+    assert_eq!(
+        diff,
+        "--- accepted\n+++ local\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+    );
+}
@@ -0,0 +1,55 @@
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::anyhow;
+
+use crate::{color::Color, LeetUpError, Result};
+
+/// Environment variable holding the user's editor, e.g. `vim` or `code -w`.
+const EDITOR_ENV: &str = "EDITOR";
+
+/// Open `path` with `$EDITOR` and wait for it to exit.
+///
+/// Only prints the path when `$EDITOR` is not set.
+pub fn open(path: &Path) -> Result<()> {
+    if editor().is_none() {
+        println!(
+            "{}",
+            Color::Yellow(&format!(
+                "$EDITOR is not set, open {} yourself",
+                path.display()
+            ))
+            .make()
+        );
+        return Ok(());
+    }
+
+    edit(path)
+}
+
+/// Edit `path` with `$EDITOR`, failing when it is not set.
+pub fn edit(path: &Path) -> Result<()> {
+    let editor = editor()
+        .ok_or_else(|| LeetUpError::Any(anyhow!("$EDITOR is not set, it is needed to edit")))?;
+    let mut words = editor.split_whitespace();
+    let program = words.next().expect("editor is not blank");
+
+    let status = Command::new(program).args(words).arg(path).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(LeetUpError::Any(anyhow!(
+            "Editor `{}` exited with {}",
+            editor,
+            status
+        )))
+    }
+}
+
+/// `$EDITOR` unless unset or blank.
+fn editor() -> Option<String> {
+    env::var(EDITOR_ENV)
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+}
@@ -31,7 +31,207 @@ pub enum LeetUpError {
     /// Unexpected Command Error
     #[error("Unexpected command")]
     UnexpectedCommand,
+
+    /// Judge did not return a verdict in time
+    #[error("Timed out waiting for the judge result of `{0}`, it may still finish later")]
+    JudgeTimeout(String),
+
+    /// Ctrl-C was pressed while waiting for the judge
+    #[error("Cancelled")]
+    Cancelled,
+
+    /// Session cookies are stale
+    #[error("LeetCode session has expired, please login again")]
+    SessionExpired,
+
+    /// Command requires a logged in user
+    #[error("You need to login first, use `leetup user -c`")]
+    NotLoggedIn,
+
+    /// Problem not found Error
+    #[error("Problem `{0}` not found")]
+    ProblemNotFound(String),
+
+    /// Premium problem whose content is withheld from this account
+    #[error(
+        "Problem `{0}` is premium only and LeetCode didn't send its content, \
+         login with a premium account to pick it"
+    )]
+    PaidOnly(String),
+
+    /// Code snippet not available for the selected language
+    #[error("Problem `{0}` has no code snippet for language `{1}`")]
+    LangNotAvailable(String, String),
+
+    /// Language not known to LeetUp
+    #[error("Language `{0}` is not supported, expected one of: {1}")]
+    LangNotSupported(String, String),
+
+    /// `--lang` disagreeing with the solution file's extension
+    #[error("`{path}` doesn't look like a {lang} solution, use --force to submit it anyway")]
+    LangMismatch { path: String, lang: String },
+
+    /// Query combining filters that exclude each other
+    #[error("Query can never match: {0} exclude each other, use --force to run it anyway")]
+    ConflictingQuery(String),
+
+    /// Network access attempted with `--offline`
+    #[error("{0} needs network access, run it without --offline")]
+    Offline(String),
+
+    /// Problems requested with `--offline` before anything was cached
+    #[error("No problems cached yet, run `leetup list` without --offline first")]
+    NoCachedProblems,
+
+    /// Config file could not be parsed
+    #[error("Invalid config file `{0}`: {1}")]
+    InvalidConfig(String, String),
+
+    /// Errors returned by a GraphQL query
+    #[error("GraphQL query failed: {0}")]
+    GraphQL(String),
+
+    /// Non-success HTTP response
+    #[error("Request failed with status {status}: {body}")]
+    Http { status: u16, body: String },
+
+    /// Solution file without a usable `@leetup=code` region
+    #[error(
+        "No code found between `@leetup=code` markers in `{path}`, put your solution between \
+         two comment lines containing `@leetup=code`, as generated by `leetup pick`"
+    )]
+    MissingCodeMarkers { path: String },
+
+    /// `list --count` matched no problems
+    #[error("No problem matches the query")]
+    NoMatches,
+}
+
+impl LeetUpError {
+    /// Process exit code for this error, so scripts can tell failures apart:
+    ///
+    /// | Code | Meaning                                              |
+    /// |------|------------------------------------------------------|
+    /// | 0    | Success                                              |
+    /// | 1    | Any other error                                      |
+    /// | 2    | Invalid query, language, config or solution file     |
+    /// | 3    | Network error, or network needed with `--offline`    |
+    /// | 4    | Not logged in or session expired                     |
+    /// | 5    | Problem or code snippet not found                    |
+    /// | 6    | `list --count` matched no problems                   |
+    /// | 130  | Ctrl-C while waiting for a judge result              |
+    pub fn exit_code(&self) -> i32 {
+        use LeetUpError::*;
+
+        match self {
+            ConflictingQuery(_)
+            | LangNotSupported(..)
+            | InvalidConfig(..)
+            | MissingCodeMarkers { .. }
+            | LangMismatch { .. } => 2,
+            Reqwest(_) | Http { .. } | GraphQL(_) | Offline(_) | NoCachedProblems => 3,
+            NotLoggedIn | SessionExpired => 4,
+            ProblemNotFound(_) | PaidOnly(_) | LangNotAvailable(..) => 5,
+            NoMatches => 6,
+            Cancelled => crate::interrupt::EXIT_CODE,
+            _ => 1,
+        }
+    }
+
+    /// Stable name of the error variant, used by `--json-errors`.
+    pub fn error_type(&self) -> &'static str {
+        use LeetUpError::*;
+
+        match self {
+            Any(_) => "other",
+            Io(_) => "io",
+            Serde(_) => "json",
+            Regex(_) => "regex",
+            Reqwest(_) => "network",
+            InvalidHeaderValue(_) => "invalid_header",
+            OptNone => "missing_value",
+            UnexpectedCommand => "unexpected_command",
+            JudgeTimeout(_) => "judge_timeout",
+            Cancelled => "cancelled",
+            SessionExpired => "session_expired",
+            NotLoggedIn => "not_logged_in",
+            ProblemNotFound(_) => "problem_not_found",
+            PaidOnly(_) => "paid_only",
+            LangNotAvailable(..) => "lang_not_available",
+            LangNotSupported(..) => "lang_not_supported",
+            LangMismatch { .. } => "lang_mismatch",
+            ConflictingQuery(_) => "conflicting_query",
+            Offline(_) => "offline",
+            NoCachedProblems => "no_cached_problems",
+            InvalidConfig(..) => "invalid_config",
+            GraphQL(_) => "graphql",
+            Http { .. } => "http",
+            MissingCodeMarkers { .. } => "missing_code_markers",
+            NoMatches => "no_matches",
+        }
+    }
+
+    /// HTTP status of the failed request, if the error came from one.
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            LeetUpError::Http { status, .. } => Some(*status),
+            LeetUpError::Reqwest(e) => e.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+
+    /// Error as printed by `--json-errors`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "error_type": self.error_type(),
+            "message": self.to_string(),
+            "http_status": self.http_status(),
+        })
+    }
 }
 
 /// Handle Result<T, LeetUpError>
 pub type Result<T> = anyhow::Result<T, LeetUpError>;
+
+#[test]
+fn test_exit_code() {
+    assert_eq!(LeetUpError::OptNone.exit_code(), 1);
+    assert_eq!(LeetUpError::ConflictingQuery("".into()).exit_code(), 2);
+    assert_eq!(LeetUpError::Offline("".into()).exit_code(), 3);
+    assert_eq!(
+        LeetUpError::Http {
+            status: 500,
+            body: "".into()
+        }
+        .exit_code(),
+        3
+    );
+    assert_eq!(LeetUpError::NotLoggedIn.exit_code(), 4);
+    assert_eq!(LeetUpError::ProblemNotFound("1".into()).exit_code(), 5);
+    assert_eq!(LeetUpError::NoMatches.exit_code(), 6);
+    assert_eq!(LeetUpError::Cancelled.exit_code(), 130);
+}
+
+#[test]
+fn test_to_json() {
+    assert_eq!(
+        LeetUpError::Http {
+            status: 502,
+            body: "Bad Gateway".into()
+        }
+        .to_json(),
+        serde_json::json!({
+            "error_type": "http",
+            "message": "Request failed with status 502: Bad Gateway",
+            "http_status": 502
+        })
+    );
+    assert_eq!(
+        LeetUpError::ProblemNotFound("1".into()).to_json(),
+        serde_json::json!({
+            "error_type": "problem_not_found",
+            "message": "Problem `1` not found",
+            "http_status": null
+        })
+    );
+}
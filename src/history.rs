@@ -0,0 +1,404 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use ansi_term::Colour::{Green, Red};
+use chrono::{Datelike, Local, TimeZone};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{cmd::create_config_directory, color::paint, Result};
+
+/// File in the leetup directory holding one submission per line.
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// Oldest submissions are dropped past this many entries.
+const MAX_ENTRIES: usize = 1000;
+
+/// Sparkline levels, lowest first, for terminals without UTF-8.
+const ASCII_LEVELS: &[char] = &['_', '.', '-', '=', '+', '*', '#'];
+
+/// Sparkline levels, lowest first.
+const BLOCK_LEVELS: &[char] = &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A submission verdict recorded after `submit`.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct HistoryEntry {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub id: usize,
+    pub slug: String,
+    pub lang: String,
+    pub verdict: String,
+    pub runtime: String,
+    pub memory: String,
+}
+
+impl HistoryEntry {
+    fn is_accepted(&self) -> bool {
+        self.verdict == "Accepted"
+    }
+}
+
+/// Local log of submissions, stored as JSON lines.
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        History {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// History stored in the leetup directory.
+    pub fn open() -> Result<Self> {
+        Ok(History::new(create_config_directory()?.join(HISTORY_FILE)))
+    }
+
+    /// Append an entry, keeping at most `MAX_ENTRIES` of the latest ones.
+    pub fn append(&self, entry: &HistoryEntry) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+
+        let lines = BufReader::new(File::open(&self.path)?).lines().count();
+        if lines > MAX_ENTRIES {
+            let entries = self.entries()?;
+            self.rewrite(&entries[entries.len().saturating_sub(MAX_ENTRIES)..])?;
+        }
+
+        Ok(())
+    }
+
+    /// All entries, oldest first. A missing file is an empty history and
+    /// corrupt lines are skipped.
+    pub fn entries(&self) -> Result<Vec<HistoryEntry>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries = vec![];
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => warn!("Skipping history line {}: {}", i + 1, e),
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Print the latest `limit` entries, newest first.
+    pub fn print(&self, limit: usize) -> Result<()> {
+        let entries = self.entries()?;
+        if entries.is_empty() {
+            println!("No submissions yet");
+            return Ok(());
+        }
+        for entry in entries.iter().rev().take(limit) {
+            let verdict = if entry.is_accepted() {
+                paint(Green, &entry.verdict)
+            } else {
+                paint(Red, &entry.verdict)
+            };
+            println!(
+                "{} [{:^4}] {:40} {:10} {} {} {}",
+                format_timestamp(entry.timestamp),
+                entry.id,
+                entry.slug,
+                entry.lang,
+                verdict,
+                entry.runtime,
+                entry.memory
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Streaks of days with an accepted submission, by local calendar day.
+    pub fn streak(&self) -> Result<Streak> {
+        let days: Vec<i64> = self
+            .entries()?
+            .iter()
+            .filter(|entry| entry.is_accepted())
+            .filter_map(|entry| local_day(entry.timestamp))
+            .collect();
+        let today = Local::now().date_naive().num_days_from_ce() as i64;
+
+        Ok(Streak::from_days(days, today))
+    }
+
+    /// Problems solved in each of the last `weeks` weeks, oldest first.
+    pub fn weekly_solved(&self, weeks: usize) -> Result<WeeklySolved> {
+        let solved: Vec<(i64, usize)> = self
+            .entries()?
+            .iter()
+            .filter(|entry| entry.is_accepted())
+            .filter_map(|entry| local_day(entry.timestamp).map(|day| (day, entry.id)))
+            .collect();
+        let today = Local::now().date_naive().num_days_from_ce() as i64;
+
+        Ok(WeeklySolved::from_days(&solved, today, weeks))
+    }
+
+    fn rewrite(&self, entries: &[HistoryEntry]) -> Result<()> {
+        let tmp = self.path.with_extension("jsonl.tmp");
+        let mut file = File::create(&tmp)?;
+        for entry in entries {
+            writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        }
+        fs::rename(tmp, &self.path)?;
+
+        Ok(())
+    }
+}
+
+/// Consecutive days with at least one accepted submission.
+#[derive(Debug, Default, PartialEq)]
+pub struct Streak {
+    /// Run ending today, or yesterday if nothing was solved yet today.
+    pub current: usize,
+    pub longest: usize,
+}
+
+impl Streak {
+    /// Streaks from the day numbers of accepted submissions, in any order.
+    pub fn from_days(mut days: Vec<i64>, today: i64) -> Self {
+        days.sort_unstable();
+        days.dedup();
+
+        let mut streak = Streak::default();
+        let mut run = 0;
+        for (i, day) in days.iter().enumerate() {
+            run = if i > 0 && days[i - 1] == day - 1 {
+                run + 1
+            } else {
+                1
+            };
+            streak.longest = streak.longest.max(run);
+        }
+        if let Some(&last) = days.last() {
+            if last == today || last == today - 1 {
+                streak.current = run;
+            }
+        }
+
+        streak
+    }
+}
+
+impl std::fmt::Display for Streak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let days = |n: usize| if n == 1 { "day" } else { "days" };
+        writeln!(f, "Current streak  {} {}", self.current, days(self.current))?;
+        writeln!(f, "Longest streak  {} {}", self.longest, days(self.longest))
+    }
+}
+
+/// Distinct problems solved per week, oldest week first. The last week
+/// ends today.
+#[derive(Debug, PartialEq)]
+pub struct WeeklySolved {
+    pub counts: Vec<usize>,
+}
+
+impl WeeklySolved {
+    /// Counts from the day numbers of accepted submissions and their problem IDs.
+    pub fn from_days(solved: &[(i64, usize)], today: i64, weeks: usize) -> Self {
+        let mut problems = vec![HashSet::new(); weeks];
+        for &(day, id) in solved {
+            let weeks_ago = (today - day).div_euclid(7);
+            if (0..weeks as i64).contains(&weeks_ago) {
+                problems[weeks - 1 - weeks_ago as usize].insert(id);
+            }
+        }
+
+        WeeklySolved {
+            counts: problems.iter().map(HashSet::len).collect(),
+        }
+    }
+
+    /// One character per week, scaled to the busiest week. Weeks without a
+    /// solved problem use the lowest level, so an empty history is flat.
+    pub fn sparkline(&self, unicode: bool) -> String {
+        let levels = if unicode { BLOCK_LEVELS } else { ASCII_LEVELS };
+        let max = self.counts.iter().copied().max().unwrap_or_default().max(1);
+        let top = levels.len() - 1;
+        self.counts
+            .iter()
+            .map(|&count| levels[(count * top).div_ceil(max)])
+            .collect()
+    }
+}
+
+impl std::fmt::Display for WeeklySolved {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total: usize = self.counts.iter().sum();
+        writeln!(
+            f,
+            "Last {} weeks  {}  {} solved",
+            self.counts.len(),
+            self.sparkline(is_utf8_locale(|name| env::var(name).ok())),
+            total
+        )
+    }
+}
+
+/// Whether the locale, from the first of `LC_ALL`, `LC_CTYPE` and `LANG`
+/// that is set, uses UTF-8. `var` looks a variable up.
+fn is_utf8_locale(var: impl Fn(&str) -> Option<String>) -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| var(name).filter(|value| !value.is_empty()))
+        .map(|locale| {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+        .unwrap_or(false)
+}
+
+/// Day number of a Unix timestamp in the local time zone.
+fn local_day(timestamp: u64) -> Option<i64> {
+    Local
+        .timestamp_opt(timestamp as i64, 0)
+        .single()
+        .map(|time| time.date_naive().num_days_from_ce() as i64)
+}
+
+/// Format seconds since the Unix epoch as `YYYY-MM-DD HH:MM` in UTC.
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let secs = timestamp % 86400;
+
+    // Days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60
+    )
+}
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00");
+    assert_eq!(format_timestamp(1_728_740_096), "2024-10-12 13:34");
+}
+
+#[test]
+fn test_history() {
+    let dir = tempfile::tempdir().unwrap();
+    let history = History::new(dir.path().join(HISTORY_FILE));
+    assert!(history.entries().unwrap().is_empty());
+
+    let entry = |id| HistoryEntry {
+        timestamp: 0,
+        id,
+        slug: "two-sum".into(),
+        lang: "rust".into(),
+        verdict: "Accepted".into(),
+        runtime: "0 ms".into(),
+        memory: "2 MB".into(),
+    };
+    history.append(&entry(1)).unwrap();
+    fs::OpenOptions::new()
+        .append(true)
+        .open(dir.path().join(HISTORY_FILE))
+        .unwrap()
+        .write_all(b"{corrupt\n")
+        .unwrap();
+    history.append(&entry(2)).unwrap();
+    assert_eq!(history.entries().unwrap(), vec![entry(1), entry(2)]);
+
+    for id in 0..MAX_ENTRIES {
+        history.append(&entry(id)).unwrap();
+    }
+    let entries = history.entries().unwrap();
+    assert_eq!(entries.len(), MAX_ENTRIES);
+    assert_eq!(entries.last(), Some(&entry(MAX_ENTRIES - 1)));
+}
+
+#[test]
+fn test_streak() {
+    let streak = |days: &[i64], today| Streak::from_days(days.to_vec(), today);
+
+    assert_eq!(streak(&[], 10), Streak::default());
+    // Solving several times a day counts once
+    assert_eq!(
+        streak(&[10, 10, 9], 10),
+        Streak {
+            current: 2,
+            longest: 2
+        }
+    );
+    // Not solved yet today, the streak is still alive
+    assert_eq!(
+        streak(&[1, 2, 3, 7, 8], 9),
+        Streak {
+            current: 2,
+            longest: 3
+        }
+    );
+    // A missed day breaks it
+    assert_eq!(
+        streak(&[5, 4, 3], 7),
+        Streak {
+            current: 0,
+            longest: 3
+        }
+    );
+    assert_eq!(
+        streak(&[3, 5], 5).to_string(),
+        "Current streak  1 day\nLongest streak  1 day\n"
+    );
+}
+
+#[test]
+fn test_weekly_solved() {
+    let weekly = WeeklySolved::from_days(&[(20, 1), (20, 1), (19, 2), (13, 3), (-1, 4)], 20, 3);
+    assert_eq!(weekly.counts, [0, 1, 2]);
+    assert_eq!(weekly.sparkline(false), "_=#");
+    assert_eq!(weekly.sparkline(true), "▁▅█");
+
+    let empty = WeeklySolved::from_days(&[], 20, 4);
+    assert_eq!(empty.sparkline(false), "____");
+    assert_eq!(empty.sparkline(true), "▁▁▁▁");
+
+    assert!(is_utf8_locale(
+        |name| (name == "LANG").then(|| "en_US.UTF-8".into())
+    ));
+    assert!(!is_utf8_locale(|name| match name {
+        "LC_ALL" => Some("C".into()),
+        _ => Some("en_US.UTF-8".into()),
+    }));
+    assert!(!is_utf8_locale(|_| None));
+}
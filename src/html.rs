@@ -0,0 +1,54 @@
+use html2text::from_read;
+use regex::{Captures, Regex};
+
+/// Render problem description HTML as plain text wrapped at `width` columns.
+///
+/// `<sup>`/`<sub>` are written as `^`/`_` so constraints like `10<sup>4</sup>`
+/// don't turn into `104`, and `<pre>` blocks are indented to stand out.
+pub fn to_text(html: &str, width: usize) -> String {
+    let html = convert_scripts(html);
+    let html = indent_pre_blocks(&html);
+    let text = from_read(html.as_bytes(), width);
+
+    text.replace("**", "")
+}
+
+fn convert_scripts(html: &str) -> String {
+    let sup = Regex::new(r"(?s)<sup>(.*?)</sup>").unwrap();
+    let sub = Regex::new(r"(?s)<sub>(.*?)</sub>").unwrap();
+    let html = sup.replace_all(html, "^$1");
+
+    sub.replace_all(&html, "_$1").into_owned()
+}
+
+fn indent_pre_blocks(html: &str) -> String {
+    let pre = Regex::new(r"(?s)(<pre>)(.*?)(</pre>)").unwrap();
+    pre.replace_all(html, |caps: &Captures| {
+        let block = caps[2]
+            .trim_matches('\n')
+            .lines()
+            .map(|line| format!("    {}", line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("{}\n{}\n{}", &caps[1], block, &caps[3])
+    })
+    .into_owned()
+}
+
+#[test]
+fn test_to_text_scripts() {
+    let html = "<p><code>1 &lt;= nums.length &lt;= 10<sup>4</sup></code> and x<sub>i</sub></p>";
+    let text = to_text(html, 80);
+
+    assert!(text.contains("1 <= nums.length <= 10^4"));
+    assert!(text.contains("x_i"));
+}
+
+#[test]
+fn test_to_text_pre_block() {
+    let html = "<p><strong>Example 1:</strong></p><pre>\n<strong>Input:</strong> nums = [2,7]\n<strong>Output:</strong> [0,1]\n</pre>";
+    let text = to_text(html, 80);
+
+    assert!(text.contains("Example 1:"));
+    assert!(text.contains("    Input: nums = [2,7]\n    Output: [0,1]"));
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 pub enum Icon {
     Yes,
     _No,
@@ -7,15 +9,16 @@ pub enum Icon {
     Empty,
 }
 
-impl ToString for Icon {
-    fn to_string(&self) -> String {
-        match self {
-            Icon::Yes => "✔".to_string(),
-            Icon::_No => "✘".to_string(),
-            Icon::Star => "★".to_string(),
-            Icon::_Unstar => "☆".to_string(),
-            Icon::Lock => "🔒".to_string(),
-            Icon::Empty => " ".to_string(),
-        }
+impl fmt::Display for Icon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let icon = match self {
+            Icon::Yes => "✔",
+            Icon::_No => "✘",
+            Icon::Star => "★",
+            Icon::_Unstar => "☆",
+            Icon::Lock => "🔒",
+            Icon::Empty => " ",
+        };
+        write!(f, "{}", icon)
     }
 }
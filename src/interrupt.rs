@@ -0,0 +1,41 @@
+use std::io::{self, Write};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::color;
+
+/// Set once Ctrl-C is pressed while waiting for the judge.
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Exit code of a process killed by SIGINT, used by shells as well.
+pub const EXIT_CODE: i32 = 130;
+
+/// Record Ctrl-C instead of killing the process, so polling loops can stop
+/// between requests and report it.
+///
+/// A second Ctrl-C exits right away, without waiting for a pending request.
+pub fn catch_ctrl_c() {
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if CANCELLED.swap(true, Ordering::SeqCst) {
+                reset_terminal();
+                eprintln!("Cancelled");
+                process::exit(EXIT_CODE);
+            }
+        }
+    });
+}
+
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// End the spinner line and clear any color left by interrupted output.
+pub fn reset_terminal() {
+    let mut stdout = io::stdout();
+    if color::is_enabled() {
+        let _ = write!(stdout, "\x1b[0m");
+    }
+    let _ = writeln!(stdout);
+    let _ = stdout.flush();
+}
@@ -0,0 +1,59 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{cmd::create_config_directory, Result};
+
+/// File in the leetup directory remembering the last picked problem.
+const LAST_PICK_FILE: &str = "last_pick.json";
+
+/// Solution file generated by the latest `pick`, submitted by `submit`
+/// when no file or problem is given.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct LastPick {
+    pub id: usize,
+    pub slug: String,
+    pub path: PathBuf,
+}
+
+impl LastPick {
+    /// Remember this pick in the leetup directory.
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&create_config_directory()?.join(LAST_PICK_FILE))
+    }
+
+    /// Last pick remembered in the leetup directory, if any.
+    pub fn load() -> Result<Option<Self>> {
+        LastPick::load_from(&create_config_directory()?.join(LAST_PICK_FILE))
+    }
+
+    fn save_to(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn load_from(path: &Path) -> Result<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[test]
+fn test_last_pick() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(LAST_PICK_FILE);
+    assert_eq!(LastPick::load_from(&path).unwrap(), None);
+
+    let pick = LastPick {
+        id: 1,
+        slug: "two-sum".into(),
+        path: dir.path().join("two-sum.rs"),
+    };
+    pick.save_to(&path).unwrap();
+    assert_eq!(LastPick::load_from(&path).unwrap(), Some(pick));
+}
@@ -6,8 +6,24 @@ mod config;
 mod error;
 mod printer;
 
+#[cfg(feature = "tui")]
+pub(crate) mod browse;
+pub(crate) mod browser;
+pub(crate) mod clean;
 pub(crate) mod client;
+pub(crate) mod color;
+pub(crate) mod diff;
+pub(crate) mod editor;
+pub(crate) mod history;
+pub(crate) mod html;
 pub(crate) mod icon;
+pub(crate) mod interrupt;
+pub(crate) mod last_pick;
 pub(crate) mod model;
+pub(crate) mod note;
+pub(crate) mod notify;
+pub(crate) mod pager;
+pub(crate) mod progress;
 pub(crate) mod service;
+pub(crate) mod snapshot;
 pub(crate) mod template;
@@ -1,8 +1,19 @@
-use leetup::{cmd, Result};
+use std::process;
+
+use leetup::{cmd, LeetUpError};
+use structopt::StructOpt;
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    env_logger::init();
-    cmd::process().await?;
-    Ok(())
+async fn main() {
+    let args = cmd::LeetUpArgs::from_args();
+    let json_errors = args.json_errors;
+    if let Err(e) = cmd::process(args).await {
+        if json_errors {
+            eprintln!("{}", e.to_json());
+        } else if !matches!(e, LeetUpError::NoMatches) {
+            // `list --count` already printed the zero count.
+            eprintln!("Error: {}", e);
+        }
+        process::exit(e.exit_code());
+    }
 }
@@ -0,0 +1,616 @@
+use std::cmp::Ordering;
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+
+use ansi_term::Colour;
+use anyhow::anyhow;
+use regex::{Captures, Regex};
+use terminal_size::{terminal_size, Width};
+
+use super::DifficultyType::{self, Easy, Hard, Medium};
+use super::ProblemInfo;
+use crate::{
+    cmd::{OrderBy, Query},
+    color::{paint, theme},
+    icon::Icon,
+    LeetUpError, Result,
+};
+
+/// Title width used when stdout is not a terminal, so redirected output
+/// doesn't depend on the terminal it was run from.
+pub const DEFAULT_TITLE_WIDTH: usize = 75;
+
+/// Narrowest title column, even on tiny terminals.
+const MIN_TITLE_WIDTH: usize = 20;
+
+/// Width of the `problem_row` columns other than the title, separators
+/// included.
+const ROW_WIDTH_WITHOUT_TITLE: usize = 28;
+
+/// Title width filling the terminal, or `DEFAULT_TITLE_WIDTH` when stdout
+/// is not a terminal.
+pub fn title_width() -> usize {
+    if !io::stdout().is_terminal() {
+        return DEFAULT_TITLE_WIDTH;
+    }
+    match terminal_size() {
+        Some((Width(width), _)) => title_width_for(width as usize),
+        None => DEFAULT_TITLE_WIDTH,
+    }
+}
+
+fn title_width_for(terminal_width: usize) -> usize {
+    terminal_width
+        .saturating_sub(ROW_WIDTH_WITHOUT_TITLE)
+        .max(MIN_TITLE_WIDTH)
+}
+
+/// Pad or cut `title` to exactly `width` characters, ending cut titles
+/// with an ellipsis.
+fn fit_title(title: &str, width: usize) -> String {
+    if title.chars().count() <= width {
+        format!("{:width$}", title, width = width)
+    } else {
+        let cut: String = title.chars().take(width.saturating_sub(1)).collect();
+        format!("{}…", cut)
+    }
+}
+
+/// Format a problem as a row of the table printed by `list`, with a title
+/// column `title_width` characters wide.
+pub fn problem_row(prob: &(dyn ProblemInfo + Send), title_width: usize) -> String {
+    let is_favorite = prob.is_favorite().unwrap_or_default();
+    let starred_icon = if is_favorite {
+        paint(theme().starred, &Icon::Star.to_string())
+    } else {
+        Icon::Empty.to_string()
+    };
+
+    // The lock is two cells wide, so pad the empty icon instead of
+    // relying on `{:2}`, which counts the escape codes when colored.
+    let locked_icon = if prob.is_paid_only() {
+        paint(theme().locked, &Icon::Lock.to_string())
+    } else {
+        Icon::Empty.to_string().repeat(2)
+    };
+
+    let acd = if prob.status().is_some() {
+        paint(theme().done, &Icon::Yes.to_string())
+    } else {
+        Icon::Empty.to_string()
+    };
+
+    let row = format!(
+        "{} {} {} [{:^4}] {} {:>6} {:6}",
+        starred_icon,
+        locked_icon,
+        acd,
+        prob.question_id(),
+        fit_title(prob.question_title(), title_width),
+        acceptance_column(prob),
+        prob.difficulty().to_string()
+    );
+    match prob.category() {
+        Some(category) => format!("{} {}", row, category),
+        None => row,
+    }
+}
+
+/// Columns accepted by `--fields`.
+pub const TABLE_FIELDS: &[&str] = &[
+    "starred",
+    "locked",
+    "status",
+    "id",
+    "title",
+    "slug",
+    "acceptance",
+    "frequency",
+    "difficulty",
+    "category",
+];
+
+/// Split a comma separated `--fields` list, rejecting unknown columns.
+pub fn parse_fields(fields: &str) -> Result<Vec<&str>> {
+    fields
+        .split(',')
+        .map(str::trim)
+        .map(|field| {
+            if TABLE_FIELDS.contains(&field) {
+                Ok(field)
+            } else {
+                Err(LeetUpError::Any(anyhow!(
+                    "Unknown field `{}`, expected one of: {}",
+                    field,
+                    TABLE_FIELDS.join(", ")
+                )))
+            }
+        })
+        .collect()
+}
+
+/// A table cell, kept plain until padded so colors don't count as width.
+struct Cell {
+    text: String,
+    width: usize,
+    colour: Option<Colour>,
+    align_right: bool,
+}
+
+impl Cell {
+    fn new(text: String) -> Self {
+        Cell {
+            width: text.chars().count(),
+            text,
+            colour: None,
+            align_right: false,
+        }
+    }
+
+    fn icon(icon: Icon, shown: bool, colour: Colour) -> Self {
+        // The lock is two cells wide, like in `problem_row`.
+        let width = if let Icon::Lock = icon { 2 } else { 1 };
+        Cell {
+            text: if shown {
+                icon.to_string()
+            } else {
+                " ".repeat(width)
+            },
+            width,
+            colour: Some(colour).filter(|_| shown),
+            align_right: false,
+        }
+    }
+
+    fn pad(&self, width: usize) -> String {
+        let padding = " ".repeat(width.saturating_sub(self.width));
+        let text = match self.colour {
+            Some(colour) => paint(colour, &self.text),
+            None => self.text.clone(),
+        };
+        if self.align_right {
+            padding + &text
+        } else {
+            text + &padding
+        }
+    }
+}
+
+fn field_cell(field: &str, prob: &(dyn ProblemInfo + Send)) -> Cell {
+    match field {
+        "starred" => Cell::icon(
+            Icon::Star,
+            prob.is_favorite().unwrap_or_default(),
+            theme().starred,
+        ),
+        "locked" => Cell::icon(Icon::Lock, prob.is_paid_only(), theme().locked),
+        "status" => Cell::icon(Icon::Yes, prob.status().is_some(), theme().done),
+        "id" => Cell {
+            align_right: true,
+            ..Cell::new(format!("[{}]", prob.question_id()))
+        },
+        "title" => Cell::new(prob.question_title().to_string()),
+        "slug" => Cell::new(prob.slug().to_string()),
+        "acceptance" => Cell {
+            align_right: true,
+            ..Cell::new(acceptance_column(prob))
+        },
+        "frequency" => Cell {
+            align_right: true,
+            ..Cell::new(
+                prob.frequency()
+                    .map(|frequency| format!("{:.1}", frequency))
+                    .unwrap_or_else(|| "-".to_string()),
+            )
+        },
+        "difficulty" => {
+            let level: DifficultyType = prob.difficulty().into();
+            Cell {
+                colour: Some(level.color()),
+                ..Cell::new(level.to_string())
+            }
+        }
+        "category" => Cell::new(prob.category().unwrap_or_default().to_string()),
+        _ => unreachable!("checked by parse_fields"),
+    }
+}
+
+/// Format problems as table rows with only the given columns, each as wide
+/// as its longest value.
+pub fn problem_table(probs: &[Box<dyn ProblemInfo + Send>], fields: &[&str]) -> Vec<String> {
+    let cells: Vec<Vec<Cell>> = probs
+        .iter()
+        .map(|prob| {
+            fields
+                .iter()
+                .map(|field| field_cell(field, prob.as_ref()))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = (0..fields.len())
+        .map(|i| cells.iter().map(|row| row[i].width).max().unwrap_or(0))
+        .collect();
+
+    cells
+        .iter()
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| cell.pad(*width))
+                .collect::<Vec<_>>()
+                .join(" ");
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
+/// Acceptance rate in percent with one decimal, `-` when unknown.
+fn acceptance_column(prob: &(dyn ProblemInfo + Send)) -> String {
+    prob.acceptance()
+        .map(|acceptance| format!("{:.1}%", acceptance * 100.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Summary line printed below the `list` table, counting the problems
+/// and solved problems per difficulty.
+pub fn difficulty_footer(probs: &[Box<dyn ProblemInfo + Send>]) -> String {
+    [Easy, Medium, Hard]
+        .iter()
+        .map(|level| {
+            let (total, solved) = probs
+                .iter()
+                .filter(|prob| DifficultyType::from(prob.difficulty()) == *level)
+                .fold((0, 0), |(total, solved), prob| {
+                    (total + 1, solved + prob.status().is_some() as usize)
+                });
+            format!(
+                "{} {} ({} solved)",
+                paint(level.color(), level.as_str()),
+                total,
+                solved
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+/// Placeholders accepted by `--format`.
+pub const FORMAT_FIELDS: &[&str] = &[
+    "id",
+    "title",
+    "slug",
+    "difficulty",
+    "acceptance",
+    "frequency",
+    "status",
+    "locked",
+    "starred",
+];
+
+/// Check that every `{field}` of a `--format` template is known.
+pub fn check_format(template: &str) -> Result<()> {
+    for caps in placeholder_regex().captures_iter(template) {
+        let field = &caps[1];
+        if !FORMAT_FIELDS.contains(&field) {
+            return Err(LeetUpError::Any(anyhow!(
+                "Unknown format field `{{{}}}`, expected one of: {}",
+                field,
+                FORMAT_FIELDS.join(", ")
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Render a problem with a `--format` template, e.g. `{id}\t{title}`.
+///
+/// `\t` and `\n` are expanded so templates can be typed in a shell.
+/// Fields are expected to be checked by `check_format` beforehand,
+/// unknown ones are left as is.
+pub fn format_problem(template: &str, prob: &(dyn ProblemInfo + Send)) -> String {
+    let template = template.replace("\\t", "\t").replace("\\n", "\n");
+    let rendered = placeholder_regex().replace_all(&template, |caps: &Captures| {
+        let difficulty: DifficultyType = prob.difficulty().into();
+        match &caps[1] {
+            "id" => prob.question_id().to_string(),
+            "title" => prob.question_title().to_string(),
+            "slug" => prob.slug().to_string(),
+            "difficulty" => difficulty.to_string(),
+            "acceptance" => prob
+                .acceptance()
+                .map(|a| format!("{:.1}", a * 100.0))
+                .unwrap_or_default(),
+            "frequency" => prob
+                .frequency()
+                .map(|f| format!("{:.1}", f))
+                .unwrap_or_default(),
+            "status" => prob.status().unwrap_or_default().to_string(),
+            "locked" => prob.is_paid_only().to_string(),
+            "starred" => prob.is_favorite().unwrap_or_default().to_string(),
+            _ => caps[0].to_string(),
+        }
+    });
+
+    rendered.into_owned()
+}
+
+fn placeholder_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\{(\w+)\}").unwrap())
+}
+
+/// Match `keyword` against the title or slug, ignoring case, or against
+/// the id when the keyword is a number.
+pub fn matches_keyword(keyword: &str, prob: &(dyn ProblemInfo + Send)) -> bool {
+    let keyword = keyword.trim().to_lowercase();
+    if !keyword.is_empty() && keyword.chars().all(|c| c.is_ascii_digit()) {
+        return keyword.parse() == Ok(prob.question_id());
+    }
+
+    prob.question_title().to_lowercase().contains(&keyword)
+        || prob.slug().contains(&keyword.replace(' ', "-"))
+}
+
+/// How closely `keyword` matches a problem, lower is closer.
+///
+/// A title or slug starting with the keyword comes first, then a word of
+/// the title starting with it, then any other match.
+pub fn keyword_rank(keyword: &str, prob: &(dyn ProblemInfo + Send)) -> Option<usize> {
+    if !matches_keyword(keyword, prob) {
+        return None;
+    }
+
+    let keyword = keyword.trim().to_lowercase();
+    let title = prob.question_title().to_lowercase();
+    let slug_keyword = keyword.replace(' ', "-");
+    if title.starts_with(&keyword) || prob.slug().starts_with(&slug_keyword) {
+        Some(0)
+    } else if title
+        .split_whitespace()
+        .any(|word| word.starts_with(&keyword))
+    {
+        Some(1)
+    } else {
+        Some(2)
+    }
+}
+
+/// Filter problems using multiple queries.
+pub fn apply_queries(queries: &[Query], o: &(dyn ProblemInfo + Send)) -> bool {
+    let mut is_satisfied = true;
+    let difficulty: DifficultyType = o.difficulty().into();
+    let is_favorite = o.is_favorite().unwrap_or_default();
+    let acceptance = o.acceptance().map(|a| a * 100.0);
+
+    for q in queries {
+        match q {
+            Query::Easy => is_satisfied &= difficulty == Easy,
+            Query::NotEasy => is_satisfied &= difficulty != Easy,
+            Query::Medium => is_satisfied &= difficulty == Medium,
+            Query::NotMedium => is_satisfied &= difficulty != Medium,
+            Query::Hard => is_satisfied &= difficulty == Hard,
+            Query::NotHard => is_satisfied &= difficulty != Hard,
+            Query::Locked => is_satisfied &= o.is_paid_only(),
+            Query::Unlocked => is_satisfied &= !o.is_paid_only(),
+            Query::Done => is_satisfied &= o.status().is_some(),
+            Query::NotDone => is_satisfied &= o.status().is_none(),
+            Query::Starred => is_satisfied &= is_favorite,
+            Query::Unstarred => is_satisfied &= !is_favorite,
+            Query::AcceptanceAbove(threshold) => {
+                is_satisfied &= acceptance.is_some_and(|a| a > *threshold)
+            }
+            Query::AcceptanceBelow(threshold) => {
+                is_satisfied &= acceptance.is_some_and(|a| a < *threshold)
+            }
+        }
+    }
+
+    is_satisfied
+}
+
+/// Order problems by Id, Title, Difficulty, Acceptance in Ascending or Descending order
+///
+/// Problems equal on every key are ordered by ascending ID, so the output
+/// doesn't depend on the order they came in.
+pub fn with_ordering(
+    orders: &[OrderBy],
+    a: &(dyn ProblemInfo + Send),
+    b: &(dyn ProblemInfo + Send),
+) -> Ordering {
+    let mut ordering = Ordering::Equal;
+    let id_ordering = a.question_id().cmp(&b.question_id());
+    let title_ordering = a.question_title().cmp(b.question_title());
+    let a_difficulty_level: DifficultyType = a.difficulty().into();
+    let b_difficulty_level: DifficultyType = b.difficulty().into();
+    let diff_ordering = a_difficulty_level.cmp(&b_difficulty_level);
+    let frequency_ordering = a
+        .frequency()
+        .partial_cmp(&b.frequency())
+        .unwrap_or(Ordering::Equal);
+    let acceptance_ordering = a
+        .acceptance()
+        .partial_cmp(&b.acceptance())
+        .unwrap_or(Ordering::Equal);
+    let new_ordering = a.is_new().cmp(&b.is_new());
+
+    for order in orders {
+        match order {
+            OrderBy::IdAsc => ordering = ordering.then(id_ordering),
+            OrderBy::IdDesc => ordering = ordering.then(id_ordering.reverse()),
+            OrderBy::TitleAsc => ordering = ordering.then(title_ordering),
+            OrderBy::TitleDesc => ordering = ordering.then(title_ordering.reverse()),
+            OrderBy::DifficultyAsc => ordering = ordering.then(diff_ordering),
+            OrderBy::DifficultyDesc => ordering = ordering.then(diff_ordering.reverse()),
+            OrderBy::AcceptanceAsc => ordering = ordering.then(acceptance_ordering),
+            OrderBy::AcceptanceDesc => ordering = ordering.then(acceptance_ordering.reverse()),
+            OrderBy::FrequencyAsc => ordering = ordering.then(frequency_ordering),
+            OrderBy::FrequencyDesc => ordering = ordering.then(frequency_ordering.reverse()),
+            OrderBy::NewAsc => ordering = ordering.then(new_ordering),
+            OrderBy::NewDesc => ordering = ordering.then(new_ordering.reverse()),
+        }
+    }
+
+    ordering.then(id_ordering)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Difficulty, TopicTagQuestion};
+
+    fn question(paid_only: bool) -> TopicTagQuestion {
+        TopicTagQuestion {
+            status: None,
+            difficulty: Difficulty::String("Easy".to_string()),
+            title: "Two Sum".to_string(),
+            is_paid_only: paid_only,
+            title_slug: "two-sum".to_string(),
+            question_frontend_id: "1".to_string(),
+            ac_rate: Some(50.0),
+        }
+    }
+
+    #[test]
+    fn test_problem_row_locked_icon() {
+        let locked = problem_row(&question(true), DEFAULT_TITLE_WIDTH);
+        let unlocked = problem_row(&question(false), DEFAULT_TITLE_WIDTH);
+
+        assert!(locked.contains(&Icon::Lock.to_string()));
+        assert!(!unlocked.contains(&Icon::Lock.to_string()));
+        assert_eq!(
+            locked.replace(&paint(theme().locked, &Icon::Lock.to_string()), "  "),
+            unlocked
+        );
+    }
+
+    #[test]
+    fn test_acceptance_column() {
+        let mut prob = question(false);
+        assert_eq!(acceptance_column(&prob), "50.0%");
+        assert!(problem_row(&prob, DEFAULT_TITLE_WIDTH).contains("  50.0% "));
+
+        prob.ac_rate = None;
+        assert_eq!(acceptance_column(&prob), "-");
+        assert!(problem_row(&prob, DEFAULT_TITLE_WIDTH).contains("      - "));
+    }
+
+    #[test]
+    fn test_problem_table() {
+        let mut long = question(true);
+        long.title = "Longest Substring".to_string();
+        long.question_frontend_id = "1234".to_string();
+        let probs: Vec<Box<dyn ProblemInfo + Send>> =
+            vec![Box::new(question(false)), Box::new(long)];
+
+        let fields = parse_fields("id, title,locked,difficulty").unwrap();
+        let rows: Vec<String> = problem_table(&probs, &fields)
+            .iter()
+            .map(strip_ansi_escapes::strip_str)
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                "   [1] Two Sum              Easy",
+                "[1234] Longest Substring 🔒 Easy",
+            ]
+        );
+        assert!(parse_fields("id,name").is_err());
+    }
+
+    #[test]
+    fn test_fit_title() {
+        assert_eq!(fit_title("Two Sum", 9), "Two Sum  ");
+        assert_eq!(fit_title("Two Sum", 7), "Two Sum");
+        assert_eq!(fit_title("Two Sum", 5), "Two …");
+        assert_eq!(title_width_for(120), 92);
+        assert_eq!(title_width_for(30), MIN_TITLE_WIDTH);
+
+        let row = problem_row(&question(false), 5);
+        assert!(row.contains("[ 1  ] Two …  50.0% "));
+    }
+
+    #[test]
+    fn test_difficulty_footer() {
+        let mut solved = question(false);
+        solved.status = Some("ac".to_string());
+        let mut hard = question(false);
+        hard.difficulty = Difficulty::String("Hard".to_string());
+        let probs: Vec<Box<dyn ProblemInfo + Send>> =
+            vec![Box::new(solved), Box::new(question(true)), Box::new(hard)];
+
+        let footer = difficulty_footer(&probs);
+        assert!(footer.contains(" 2 (1 solved)"));
+        assert!(footer.contains(" 0 (0 solved)"));
+        assert!(footer.ends_with(" 1 (0 solved)"));
+    }
+
+    #[test]
+    fn test_matches_keyword() {
+        let prob = question(false);
+        assert!(matches_keyword("", &prob));
+        assert!(matches_keyword("two sum", &prob));
+        assert!(matches_keyword("TWO", &prob));
+        assert!(matches_keyword("two-sum", &prob));
+        assert!(matches_keyword("1", &prob));
+        assert!(!matches_keyword("11", &prob));
+        assert!(!matches_keyword("three", &prob));
+    }
+
+    #[test]
+    fn test_keyword_rank() {
+        let prob = question(false);
+        assert_eq!(keyword_rank("two", &prob), Some(0));
+        assert_eq!(keyword_rank("sum", &prob), Some(1));
+        assert_eq!(keyword_rank("o s", &prob), Some(2));
+        assert_eq!(keyword_rank("three", &prob), None);
+    }
+
+    #[test]
+    fn test_format_problem() {
+        let prob = question(true);
+        assert_eq!(
+            format_problem(r"{id}\t{title}|{difficulty}|{acceptance}|{locked}", &prob),
+            "1\tTwo Sum|Easy|50.0|true"
+        );
+        assert_eq!(format_problem("{slug} {frequency}", &prob), "two-sum ");
+        assert_eq!(format_problem("{id} {name}", &prob), "1 {name}");
+
+        assert!(check_format("{id}\t{title}").is_ok());
+        let err = check_format("{id} {name}").unwrap_err().to_string();
+        assert!(err.contains("Unknown format field `{name}`"));
+        assert!(err.contains("id, title, slug"));
+    }
+
+    #[test]
+    fn test_apply_queries() {
+        let locked = question(true);
+        assert!(apply_queries(&[Query::Easy, Query::Locked], &locked));
+        assert!(!apply_queries(&[Query::Unlocked], &locked));
+        assert!(apply_queries(&[Query::AcceptanceAbove(40.0)], &locked));
+    }
+
+    #[test]
+    fn test_ordering_ties_by_id() {
+        let problem = |id: &str| {
+            let mut prob = question(false);
+            prob.question_frontend_id = id.to_string();
+            Box::new(prob) as Box<dyn ProblemInfo + Send>
+        };
+        let sorted = |ids: [&str; 3]| {
+            let mut probs: Vec<_> = ids.iter().map(|id| problem(id)).collect();
+            probs.sort_by(|a, b| {
+                with_ordering(
+                    &[OrderBy::DifficultyAsc, OrderBy::AcceptanceDesc],
+                    a.as_ref(),
+                    b.as_ref(),
+                )
+            });
+            probs.iter().map(|p| p.question_id()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(sorted(["3", "1", "2"]), [1, 2, 3]);
+        assert_eq!(sorted(["2", "3", "1"]), sorted(["3", "1", "2"]));
+    }
+}
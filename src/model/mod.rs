@@ -1,13 +1,23 @@
 use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::Write;
 use std::str::FromStr;
 
-use ansi_term::Color::{Green, Red, Yellow};
-use serde::Deserialize;
-use serde_repr::{Deserialize_repr, Serialize_repr};
+use ansi_term::Color::{self, Purple};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use DifficultyType::*;
 
-use crate::{Either, LeetUpError};
+use crate::{
+    cmd::ExportFormat,
+    color::{paint, theme},
+    Either, LeetUpError, Result, ScoreWeights,
+};
+
+pub use list::*;
+
+mod list;
 
 #[derive(Debug)]
 pub struct Problem {
@@ -16,66 +26,107 @@ pub struct Problem {
     pub lang: String,
     pub link: String,
     pub typed_code: Option<String>,
+
+    /// Unknown for problems read back from a solution file
+    pub difficulty: DifficultyType,
 }
 
-#[derive(Ord, PartialOrd, Eq, PartialEq, Clone, Serialize_repr, Deserialize_repr, Debug)]
-#[repr(u8)]
+/// Difficulty level, sent as `1`, `2` or `3` by LeetCode.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Clone, Copy, Debug)]
 pub enum DifficultyType {
     Easy = 1,
     Medium,
     Hard,
+    Unknown,
+}
+
+impl DifficultyType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Easy => "Easy",
+            Medium => "Medium",
+            Hard => "Hard",
+            Unknown => "Unknown",
+        }
+    }
+
+    /// Color used when printing the level.
+    pub fn color(&self) -> Color {
+        let theme = theme();
+        match self {
+            Easy => theme.easy,
+            Medium => theme.medium,
+            Hard => theme.hard,
+            Unknown => Purple,
+        }
+    }
+}
+
+impl From<u8> for DifficultyType {
+    fn from(level: u8) -> Self {
+        match level {
+            1 => Easy,
+            2 => Medium,
+            3 => Hard,
+            _ => Unknown,
+        }
+    }
+}
+
+impl Serialize for DifficultyType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Unknown => serializer.serialize_u8(0),
+            level => serializer.serialize_u8(*level as u8),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DifficultyType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Ok(u8::deserialize(deserializer)?.into())
+    }
 }
 
 impl FromStr for DifficultyType {
     type Err = LeetUpError;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let easy = Easy.to_string();
-        let medium = Medium.to_string();
-        let hard = Hard.to_string();
         match s {
-            x if x == easy => Ok(Easy),
-            x if x == medium => Ok(Medium),
-            x if x == hard => Ok(Hard),
+            "Easy" => Ok(Easy),
+            "Medium" => Ok(Medium),
+            "Hard" => Ok(Hard),
             _ => Err(LeetUpError::UnexpectedCommand),
         }
     }
 }
 
-impl ToString for DifficultyType {
-    fn to_string(&self) -> String {
-        match self {
-            Easy => "Easy".into(),
-            Medium => "Medium".into(),
-            Hard => "Hard".into(),
-        }
+impl fmt::Display for DifficultyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(untagged)]
 pub enum Difficulty {
     Cardinal { level: DifficultyType },
     String(String),
 }
 
-impl<'a> From<&'_ Difficulty> for DifficultyType {
+impl From<&Difficulty> for DifficultyType {
     fn from(difficulty: &Difficulty) -> Self {
         match difficulty {
-            Difficulty::Cardinal { level } => level.clone(),
-            Difficulty::String(s) => DifficultyType::from_str(s).unwrap(),
+            Difficulty::Cardinal { level } => *level,
+            Difficulty::String(s) => DifficultyType::from_str(s).unwrap_or(Unknown),
         }
     }
 }
 
-impl ToString for Difficulty {
-    fn to_string(&self) -> String {
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let level: DifficultyType = self.into();
-        match level {
-            Easy => Green.paint(Easy.to_string()).to_string(),
-            Medium => Yellow.paint(Medium.to_string()).to_string(),
-            Hard => Red.paint(Hard.to_string()).to_string(),
-        }
+        write!(f, "{}", paint(level.color(), level.as_str()))
     }
 }
 
@@ -84,6 +135,25 @@ pub type ProblemInfoSeq = Vec<Box<dyn ProblemInfo + Send + 'static>>;
 pub trait ProblemInfo {
     fn question_id(&self) -> usize;
     fn question_title(&self) -> &str;
+    fn slug(&self) -> &str;
+
+    /// Ratio of accepted over total submissions, if known.
+    fn acceptance(&self) -> Option<f64>;
+
+    /// Accepted and total submissions, if known.
+    fn submissions(&self) -> Option<(usize, usize)>;
+
+    /// How often the problem is asked in interviews, if known.
+    fn frequency(&self) -> Option<f64>;
+
+    /// Progress reported by LeetCode for the user, if known.
+    fn progress(&self) -> Option<f64>;
+
+    /// Whether LeetCode marks the problem as newly added, if known.
+    fn is_new(&self) -> Option<bool>;
+
+    /// Category the problem was listed under, if fetched per category.
+    fn category(&self) -> Option<&str>;
     fn difficulty(&self) -> &Difficulty;
     fn is_favorite(&self) -> Option<bool>;
     fn is_paid_only(&self) -> bool;
@@ -130,25 +200,135 @@ impl Ord for dyn ProblemInfo {
     }
 }
 
+/// Plain, uncolored view of a problem used for machine readable output.
+///
+/// Every field is always present, unknown values are `null`, so the shape of
+/// `list --output json` stays stable whatever the problems come from.
+#[derive(Serialize, Debug)]
+pub struct ProblemSummary {
+    pub id: usize,
+    pub title: String,
+    pub slug: String,
+    pub difficulty: String,
+    pub locked: bool,
+    pub starred: bool,
+    pub done: bool,
+
+    /// Accepted over total submissions, between 0 and 1
+    pub acceptance: Option<f64>,
+    pub total_acs: Option<usize>,
+    pub total_submitted: Option<usize>,
+    pub frequency: Option<f64>,
+    pub progress: Option<f64>,
+    pub category: Option<String>,
+}
+
+/// Problem written by `export`, its summary and topic tags. `tags` is
+/// `null` unless they were requested.
+#[derive(Serialize, Debug)]
+pub struct ExportedProblem {
+    #[serde(flatten)]
+    pub summary: ProblemSummary,
+    pub tags: Option<Vec<String>>,
+}
+
+/// JSON Schema of an `ExportedProblem`, printed by `export --schema`.
+pub fn export_schema() -> serde_json::Value {
+    let number = serde_json::json!({ "type": ["number", "null"] });
+    let integer = serde_json::json!({ "type": ["integer", "null"], "minimum": 0 });
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "LeetUp exported problem",
+        "type": "object",
+        "required": [
+            "id", "title", "slug", "difficulty", "locked", "starred", "done", "acceptance",
+            "total_acs", "total_submitted", "frequency", "progress", "category", "tags"
+        ],
+        "properties": {
+            "id": { "type": "integer", "minimum": 0 },
+            "title": { "type": "string" },
+            "slug": { "type": "string" },
+            "difficulty": { "enum": ["Easy", "Medium", "Hard", "Unknown"] },
+            "locked": { "type": "boolean" },
+            "starred": { "type": "boolean" },
+            "done": { "type": "boolean" },
+            "acceptance": { "type": ["number", "null"], "minimum": 0, "maximum": 1 },
+            "total_acs": integer,
+            "total_submitted": integer,
+            "frequency": number,
+            "progress": number,
+            "category": { "type": ["string", "null"] },
+            "tags": { "type": ["array", "null"], "items": { "type": "string" } }
+        }
+    })
+}
+
+/// Write `problems` to `out` one at a time, as a JSON array or one JSON
+/// object per line, so the whole output is never held in memory.
+pub fn write_export<W: Write>(
+    mut out: W,
+    problems: impl Iterator<Item = ExportedProblem>,
+    format: ExportFormat,
+    pretty: bool,
+) -> Result<()> {
+    let pretty = pretty && format == ExportFormat::Json;
+    if format == ExportFormat::Json {
+        out.write_all(b"[")?;
+    }
+    for (i, problem) in problems.enumerate() {
+        match format {
+            ExportFormat::Json if i > 0 => out.write_all(b",")?,
+            _ => {}
+        }
+        if pretty {
+            out.write_all(b"\n")?;
+            serde_json::to_writer_pretty(&mut out, &problem)?;
+        } else {
+            serde_json::to_writer(&mut out, &problem)?;
+        }
+        if format == ExportFormat::Ndjson {
+            out.write_all(b"\n")?;
+        }
+    }
+    if format == ExportFormat::Json {
+        out.write_all(if pretty { "\n]\n" } else { "]\n" }.as_bytes())?;
+    }
+    out.flush()?;
+
+    Ok(())
+}
+
+impl From<&(dyn ProblemInfo + Send + '_)> for ProblemSummary {
+    fn from(prob: &(dyn ProblemInfo + Send + '_)) -> Self {
+        let difficulty: DifficultyType = prob.difficulty().into();
+        ProblemSummary {
+            id: prob.question_id(),
+            title: prob.question_title().to_string(),
+            slug: prob.slug().to_string(),
+            difficulty: difficulty.to_string(),
+            locked: prob.is_paid_only(),
+            starred: prob.is_favorite().unwrap_or_default(),
+            done: prob.status().is_some(),
+            acceptance: prob.acceptance(),
+            total_acs: prob.submissions().map(|(accepted, _)| accepted),
+            total_submitted: prob.submissions().map(|(_, total)| total),
+            frequency: prob.frequency(),
+            progress: prob.progress(),
+            category: prob.category().map(String::from),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Stat {
     pub question_id: usize,
 
-    #[serde(rename = "question__article__live")]
-    pub question_article_live: Option<bool>,
-
-    #[serde(rename = "question__article__slug")]
-    pub question_article_slug: Option<String>,
-
     #[serde(rename = "question__title")]
     pub question_title: String,
 
     #[serde(rename = "question__title_slug")]
     pub question_title_slug: String,
 
-    #[serde(rename = "question__hide")]
-    pub question_hide: bool,
-
     pub total_acs: usize,
     pub total_submitted: usize,
     pub frontend_question_id: usize,
@@ -164,9 +344,13 @@ pub struct StatStatusPair {
     pub is_favor: bool,
     pub frequency: f64,
     pub progress: f64,
+
+    /// Category the problem was listed under, set when merging categories.
+    #[serde(skip)]
+    pub category: Option<String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct TopicTagQuestion {
     pub status: Option<String>,
     pub difficulty: Difficulty,
@@ -180,6 +364,195 @@ pub struct TopicTagQuestion {
 
     #[serde(rename = "questionFrontendId")]
     pub question_frontend_id: String,
+
+    /// Acceptance rate in percent
+    #[serde(rename = "acRate")]
+    pub ac_rate: Option<f64>,
+}
+
+/// Questions of a topic or company tag.
+#[derive(Deserialize, Debug)]
+pub struct TagQuestions {
+    pub questions: Vec<TopicTagQuestion>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TopicTagData {
+    #[serde(rename = "topicTag")]
+    pub topic_tag: Option<TagQuestions>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CompanyTagData {
+    #[serde(rename = "companyTag")]
+    pub company_tag: Option<TagQuestions>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct QuestionData {
+    pub question: Option<QuestionDetail>,
+}
+
+/// Question fields returned by the `getQuestionDetail` query, all optional
+/// so the query can ask for a subset.
+#[derive(Deserialize, Debug, Default)]
+pub struct QuestionDetail {
+    pub content: Option<String>,
+
+    /// Chinese description, only on leetcode.cn
+    #[serde(rename = "translatedContent")]
+    pub translated_content: Option<String>,
+
+    /// JSON encoded list of `CodeDefinition`
+    #[serde(rename = "codeDefinition")]
+    pub code_definition: Option<String>,
+
+    #[serde(rename = "sampleTestCase")]
+    pub sample_test_case: Option<String>,
+
+    /// Official hints as HTML, in the order they should be revealed
+    pub hints: Option<Vec<String>>,
+
+    /// JSON encoded list of `SimilarQuestion`
+    #[serde(rename = "similarQuestions")]
+    pub similar_questions: Option<String>,
+
+    pub likes: Option<usize>,
+    pub dislikes: Option<usize>,
+
+    #[serde(rename = "topicTags")]
+    pub topic_tags: Option<Vec<TopicTag>>,
+}
+
+impl QuestionDetail {
+    /// Replace the description with its translation, if there is one.
+    ///
+    /// Returns whether the translation was used.
+    pub fn use_translation(&mut self) -> bool {
+        match self.translated_content.take() {
+            Some(translated) if !translated.trim().is_empty() => {
+                self.content = Some(translated);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether LeetCode withheld the description and code, as it does for
+    /// premium problems without a subscription.
+    pub fn is_withheld(&self) -> bool {
+        let is_blank =
+            |field: &Option<String>| field.as_deref().is_none_or(|s| s.trim().is_empty());
+        is_blank(&self.content) && is_blank(&self.code_definition)
+    }
+}
+
+/// `detail` of a problem to pick, turned into `LeetUpError::PaidOnly` when
+/// the problem is premium only and LeetCode withheld or didn't find it.
+pub fn check_paid_only(
+    slug: &str,
+    paid_only: bool,
+    detail: Result<QuestionDetail>,
+) -> Result<QuestionDetail> {
+    match detail {
+        Ok(detail) if paid_only && detail.is_withheld() => {
+            Err(LeetUpError::PaidOnly(slug.to_string()))
+        }
+        Err(LeetUpError::ProblemNotFound(_)) if paid_only => {
+            Err(LeetUpError::PaidOnly(slug.to_string()))
+        }
+        detail => detail,
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TopicTag {
+    pub name: String,
+}
+
+/// Everything `info` shows about a problem, one field per line.
+///
+/// Quick fields come from the problem list, the others from `detail` and
+/// are left out when unknown.
+pub fn problem_info(item: &StatStatusPair, detail: &QuestionDetail, url: &str) -> String {
+    let mut lines = vec![
+        format!("[{}] {}", item.question_id(), item.question_title()),
+        format!("Difficulty  {}", item.difficulty),
+    ];
+    if let Some(acceptance) = item.acceptance() {
+        lines.push(format!(
+            "Acceptance  {:.1}% ({}/{})",
+            acceptance * 100.0,
+            item.stat.total_acs,
+            item.stat.total_submitted
+        ));
+    }
+    lines.push(format!(
+        "Premium     {}",
+        if item.paid_only { "yes" } else { "no" }
+    ));
+    if let Some(tags) = detail.topic_tags.as_ref().filter(|tags| !tags.is_empty()) {
+        let names: Vec<&str> = tags.iter().map(|tag| tag.name.as_str()).collect();
+        lines.push(format!("Tags        {}", names.join(", ")));
+    }
+    if let (Some(likes), Some(dislikes)) = (detail.likes, detail.dislikes) {
+        lines.push(format!("Likes       {} / {} dislikes", likes, dislikes));
+    }
+    lines.push(format!("URL         {}", url));
+
+    lines.join("\n")
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SimilarQuestion {
+    #[serde(rename = "titleSlug")]
+    pub title_slug: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DailyChallengeData {
+    #[serde(rename = "activeDailyCodingChallengeQuestion")]
+    pub daily_challenge: DailyChallenge,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DailyChallenge {
+    /// Date of the challenge in UTC, e.g. 2024-10-12
+    pub date: String,
+
+    /// Problem path relative to the site, e.g. /problems/two-sum/
+    pub link: String,
+
+    pub question: DailyQuestion,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DailyQuestion {
+    pub title: String,
+    pub difficulty: Difficulty,
+
+    #[serde(rename = "titleSlug")]
+    pub title_slug: String,
+
+    #[serde(rename = "questionFrontendId")]
+    pub question_frontend_id: String,
+}
+
+/// Favorite lists of the logged in user.
+#[derive(Deserialize, Debug)]
+pub struct FavoritesResponse {
+    pub favorites: Favorites,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Favorites {
+    pub private_favorites: Vec<FavoriteList>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FavoriteList {
+    pub id_hash: String,
+    pub name: String,
 }
 
 #[derive(Deserialize, Debug)]
@@ -191,15 +564,227 @@ pub struct ListResponse {
     pub ac_medium: usize,
     pub ac_hard: usize,
     pub stat_status_pairs: Vec<StatStatusPair>,
-    pub frequency_high: usize,
-    pub frequency_mid: usize,
     pub category_slug: String,
 }
 
+/// Merge the problems of per category listings, tagging each problem with
+/// the category it came from. A problem listed under several categories is
+/// kept once, under the first one.
+pub fn merge_categories(lists: Vec<ListResponse>) -> Vec<StatStatusPair> {
+    let mut seen = HashSet::new();
+    let mut problems = vec![];
+    for list in lists {
+        for mut pair in list.stat_status_pairs {
+            if seen.insert(pair.stat.question_id) {
+                pair.category = Some(list.category_slug.clone());
+                problems.push(pair);
+            }
+        }
+    }
+
+    problems
+}
+
+/// Number of problems per tag, most frequent first, then by name.
+pub fn tag_histogram(tags: &[Vec<String>]) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = vec![];
+    for tag in tags.iter().flatten() {
+        match counts.iter_mut().find(|(name, _)| name == tag) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((tag.to_owned(), 1)),
+        }
+    }
+    counts.sort_by(|(a_name, a_count), (b_name, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+    });
+
+    counts
+}
+
+/// Counts as a bar chart, the longest bar being `width` characters.
+pub fn format_histogram(counts: &[(String, usize)], width: usize) -> String {
+    let name_width = counts
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let max = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+
+    counts
+        .iter()
+        .map(|(name, count)| {
+            // Every non-zero count gets at least one block so small counts
+            // stay visible.
+            let bar = (count * width / max.max(1)).max(usize::from(*count > 0));
+            format!(
+                "{:name_width$} {:>4} {}\n",
+                name,
+                count,
+                "█".repeat(bar),
+                name_width = name_width
+            )
+        })
+        .collect()
+}
+
+/// Solved over total problems, overall and per difficulty.
+#[derive(Debug, Default, PartialEq)]
+pub struct ProblemStats {
+    pub logged_in: bool,
+    pub solved: usize,
+    pub total: usize,
+    pub easy: (usize, usize),
+    pub medium: (usize, usize),
+    pub hard: (usize, usize),
+}
+
+impl From<&ListResponse> for ProblemStats {
+    fn from(res: &ListResponse) -> Self {
+        let mut stats = ProblemStats {
+            logged_in: !res.user_name.is_empty(),
+            solved: res.num_solved,
+            total: res.num_total,
+            easy: (res.ac_easy, 0),
+            medium: (res.ac_medium, 0),
+            hard: (res.ac_hard, 0),
+        };
+        for pair in &res.stat_status_pairs {
+            match DifficultyType::from(&pair.difficulty) {
+                Easy => stats.easy.1 += 1,
+                Medium => stats.medium.1 += 1,
+                Hard => stats.hard.1 += 1,
+                Unknown => {}
+            }
+        }
+
+        stats
+    }
+}
+
+impl ProblemStats {
+    /// Practice score of the solved problems, weighted by difficulty.
+    pub fn score(&self, weights: &ScoreWeights) -> Score {
+        let parts = vec![
+            (Easy, self.easy.0, weights.easy),
+            (Medium, self.medium.0, weights.medium),
+            (Hard, self.hard.0, weights.hard),
+        ];
+        Score {
+            total: parts
+                .iter()
+                .map(|(_, solved, weight)| solved * weight)
+                .sum(),
+            parts,
+        }
+    }
+}
+
+impl fmt::Display for ProblemStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn percent(solved: usize, total: usize) -> f64 {
+            if total == 0 {
+                0.0
+            } else {
+                solved as f64 * 100.0 / total as f64
+            }
+        }
+
+        writeln!(
+            f,
+            "Solved  {:>5}/{:<5} {:>5.1}%",
+            self.solved,
+            self.total,
+            percent(self.solved, self.total)
+        )?;
+        for (level, (solved, total)) in
+            [(Easy, self.easy), (Medium, self.medium), (Hard, self.hard)]
+        {
+            writeln!(
+                f,
+                "{} {:>5}/{:<5} {:>5.1}%",
+                paint(level.color(), &format!("{:<7}", level.to_string())),
+                solved,
+                total,
+                percent(solved, total)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Weighted score printed by `stats`.
+#[derive(Debug, PartialEq)]
+pub struct Score {
+    pub total: usize,
+
+    /// Difficulty, solved problems and points per problem
+    pub parts: Vec<(DifficultyType, usize, usize)>,
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Score   {:>5}", self.total)?;
+        for (level, solved, weight) in &self.parts {
+            writeln!(
+                f,
+                "{} {:>5} x {:<3} = {}",
+                paint(level.color(), &format!("{:<7}", level.to_string())),
+                solved,
+                weight,
+                solved * weight
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Number of problems per acceptance rate range, printed by `stats`.
+#[derive(Debug, PartialEq)]
+pub struct AcceptanceBuckets {
+    /// Width of each range, in percent.
+    pub width: u32,
+
+    /// Problems per range, lowest acceptance first.
+    pub counts: Vec<usize>,
+}
+
+impl AcceptanceBuckets {
+    /// Count `probs` in ranges of `width` percent. Problems without any
+    /// submission have no acceptance rate and are skipped.
+    pub fn new<'a>(probs: impl IntoIterator<Item = &'a StatStatusPair>, width: u32) -> Self {
+        let width = width.clamp(1, 100);
+        let mut counts = vec![0; 100u32.div_ceil(width) as usize];
+        let last = counts.len() - 1;
+        for prob in probs {
+            if let Some(acceptance) = prob.acceptance() {
+                // 100% belongs to the last range.
+                let bucket = (acceptance * 100.0 / width as f64) as usize;
+                counts[bucket.min(last)] += 1;
+            }
+        }
+
+        AcceptanceBuckets { width, counts }
+    }
+
+    /// Ranges such as `20-40%` with their number of problems.
+    pub fn ranges(&self) -> Vec<(String, usize)> {
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| {
+                let start = i as u32 * self.width;
+                let end = (start + self.width).min(100);
+                (format!("{}-{}%", start, end), count)
+            })
+            .collect()
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct CodeDefinition {
     pub value: String,
-    pub text: String,
 
     #[serde(rename = "defaultCode")]
     pub default_code: String,
@@ -207,36 +792,44 @@ pub struct CodeDefinition {
 
 #[derive(Deserialize, Debug)]
 pub struct SubmissionResponse {
-    pub state: Option<String>,
     pub input: Option<Either>,
-    pub input_formatted: Option<Either>,
     pub code_output: Option<Either>,
-    pub std_output: Option<Either>,
     pub last_test_case: Option<Either>,
-    pub correct_answer: Option<bool>,
     pub code_answer: Option<Either>,
     pub expected_output: Option<Either>,
-    pub expected_code_output: Option<Either>,
-    pub expected_answer: Option<Either>,
     pub expected_code_answer: Option<Either>,
-    pub compare_result: Option<String>,
     pub compile_error: Option<String>,
     pub full_compile_error: Option<String>,
-    pub lang: String,
-    pub memory: Option<u32>,
     pub memory_percentile: Option<f32>,
-    pub pretty_lang: String,
-    pub run_success: bool,
     pub runtime_percentile: Option<f32>,
-    pub expected_status_code: Option<u32>,
     pub status_memory: String,
     pub status_msg: String,
     pub status_runtime: String,
-    pub submission_id: String,
     pub total_correct: Option<u32>,
     pub total_testcases: Option<u32>,
 }
 
+/// One page of `/api/submissions/<slug>`, latest first.
+#[derive(Deserialize, Debug)]
+pub struct SubmissionList {
+    pub submissions_dump: Vec<SubmissionDump>,
+    pub has_next: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SubmissionDump {
+    pub id: usize,
+    pub lang: String,
+    pub status_display: String,
+    pub code: String,
+}
+
+impl SubmissionDump {
+    pub fn is_accepted(&self) -> bool {
+        self.status_display == "Accepted"
+    }
+}
+
 pub trait ExecutionErrorResponse {
     fn has_compile_error(&self) -> bool;
 
@@ -267,6 +860,7 @@ impl ExecutionErrorResponse for SubmissionResponse {
 }
 
 impl ProblemInfo for StatStatusPair {
+    #[allow(clippy::misnamed_getters)]
     fn question_id(&self) -> usize {
         self.stat.frontend_question_id
     }
@@ -275,6 +869,37 @@ impl ProblemInfo for StatStatusPair {
         self.stat.question_title.as_str()
     }
 
+    fn slug(&self) -> &str {
+        self.stat.question_title_slug.as_str()
+    }
+
+    fn acceptance(&self) -> Option<f64> {
+        if self.stat.total_submitted == 0 {
+            return None;
+        }
+        Some(self.stat.total_acs as f64 / self.stat.total_submitted as f64)
+    }
+
+    fn submissions(&self) -> Option<(usize, usize)> {
+        Some((self.stat.total_acs, self.stat.total_submitted))
+    }
+
+    fn frequency(&self) -> Option<f64> {
+        Some(self.frequency)
+    }
+
+    fn progress(&self) -> Option<f64> {
+        Some(self.progress)
+    }
+
+    fn is_new(&self) -> Option<bool> {
+        Some(self.stat.is_new_question)
+    }
+
+    fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
     fn difficulty(&self) -> &Difficulty {
         &self.difficulty
     }
@@ -303,6 +928,34 @@ impl ProblemInfo for TopicTagQuestion {
         self.title.as_str()
     }
 
+    fn slug(&self) -> &str {
+        self.title_slug.as_str()
+    }
+
+    fn acceptance(&self) -> Option<f64> {
+        self.ac_rate.map(|rate| rate / 100.0)
+    }
+
+    fn submissions(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    fn frequency(&self) -> Option<f64> {
+        None
+    }
+
+    fn progress(&self) -> Option<f64> {
+        None
+    }
+
+    fn is_new(&self) -> Option<bool> {
+        None
+    }
+
+    fn category(&self) -> Option<&str> {
+        None
+    }
+
     fn difficulty(&self) -> &Difficulty {
         &self.difficulty
     }
@@ -319,3 +972,362 @@ impl ProblemInfo for TopicTagQuestion {
         self.status.as_ref().map(String::as_ref)
     }
 }
+
+/// Unsolved, free, easy problem `id` as listed by `/api/problems/all`.
+#[cfg(test)]
+pub(crate) fn stat_pair(id: usize, title: &str, acs: usize, submitted: usize) -> StatStatusPair {
+    StatStatusPair {
+        stat: Stat {
+            question_id: id,
+            question_title: title.to_string(),
+            question_title_slug: title.to_lowercase().replace(' ', "-"),
+            total_acs: acs,
+            total_submitted: submitted,
+            frontend_question_id: id,
+            is_new_question: false,
+        },
+        status: None,
+        difficulty: Difficulty::Cardinal {
+            level: DifficultyType::Easy,
+        },
+        paid_only: false,
+        is_favor: false,
+        frequency: 0.0,
+        progress: 0.0,
+        category: None,
+    }
+}
+
+#[cfg(test)]
+fn list_response(category: &str, pairs: Vec<StatStatusPair>) -> ListResponse {
+    ListResponse {
+        user_name: String::new(),
+        num_solved: 0,
+        num_total: pairs.len(),
+        ac_easy: 0,
+        ac_medium: 0,
+        ac_hard: 0,
+        stat_status_pairs: pairs,
+        category_slug: category.to_string(),
+    }
+}
+
+#[test]
+fn test_problem_stats() {
+    let pair = |level| {
+        let mut pair = stat_pair(1, "Two Sum", 1, 2);
+        pair.difficulty = Difficulty::Cardinal { level };
+        pair
+    };
+    let mut res = list_response(
+        "all",
+        vec![pair(Easy), pair(Easy), pair(Medium), pair(Hard)],
+    );
+    res.num_solved = 2;
+    res.ac_easy = 1;
+    res.ac_medium = 1;
+
+    let stats = ProblemStats::from(&res);
+    assert_eq!(
+        stats,
+        ProblemStats {
+            logged_in: false,
+            solved: 2,
+            total: 4,
+            easy: (1, 2),
+            medium: (1, 1),
+            hard: (0, 1),
+        }
+    );
+    let text = stats.to_string();
+    assert!(text.contains("Solved      2/4      50.0%"));
+    assert!(text.contains("    1/2      50.0%"));
+    assert!(text.contains("    0/1       0.0%"));
+
+    let score = stats.score(&ScoreWeights::default());
+    assert_eq!(score.total, 4);
+    assert_eq!(
+        score.parts,
+        vec![(Easy, 1, 1), (Medium, 1, 3), (Hard, 0, 5)]
+    );
+    let weights = ScoreWeights {
+        easy: 2,
+        medium: 0,
+        hard: 5,
+    };
+    assert_eq!(stats.score(&weights).total, 2);
+    assert!(score.to_string().contains("    1 x 3   = 3"));
+}
+
+#[test]
+fn test_difficulty_wire_format() {
+    let level = |value: serde_json::Value| {
+        DifficultyType::from(&serde_json::from_value::<Difficulty>(value).unwrap())
+    };
+    assert_eq!(level(serde_json::json!({ "level": 2 })), Medium);
+    assert_eq!(level(serde_json::json!({ "level": 7 })), Unknown);
+    assert_eq!(level(serde_json::json!("Hard")), Hard);
+    assert_eq!(level(serde_json::json!("Impossible")), Unknown);
+    assert_eq!(serde_json::to_string(&Hard).unwrap(), "3");
+    assert_eq!(Medium.as_str(), "Medium");
+    assert!(Hard > Easy);
+}
+
+#[test]
+fn test_similar_questions() {
+    let detail: QuestionDetail = serde_json::from_value(serde_json::json!({
+        "similarQuestions": r#"[{"title":"3Sum","titleSlug":"3sum","difficulty":"Medium","translatedTitle":null}]"#
+    }))
+    .unwrap();
+    let similar: Vec<SimilarQuestion> =
+        serde_json::from_str(detail.similar_questions.as_deref().unwrap()).unwrap();
+    assert_eq!(similar.len(), 1);
+    assert_eq!(similar[0].title_slug, "3sum");
+}
+
+#[test]
+fn test_problem_summary_json() {
+    let mut pair = stat_pair(1, "Two Sum", 1, 4);
+    pair.status = Some("ac".to_string());
+    pair.is_favor = true;
+    pair.frequency = 2.5;
+
+    let summary = ProblemSummary::from(&pair as &(dyn ProblemInfo + Send));
+    assert_eq!(
+        serde_json::to_value(summary).unwrap(),
+        serde_json::json!({
+            "id": 1,
+            "title": "Two Sum",
+            "slug": "two-sum",
+            "difficulty": "Easy",
+            "locked": false,
+            "starred": true,
+            "done": true,
+            "acceptance": 0.25,
+            "total_acs": 1,
+            "total_submitted": 4,
+            "frequency": 2.5,
+            "progress": 0.0,
+            "category": null
+        })
+    );
+}
+
+#[test]
+fn test_write_export() {
+    let exported = |tags: Option<Vec<String>>| {
+        let pair = stat_pair(1, "Two Sum", 1, 4);
+        ExportedProblem {
+            summary: ProblemSummary::from(&pair as &(dyn ProblemInfo + Send)),
+            tags,
+        }
+    };
+    let export = |problems: Vec<ExportedProblem>, format, pretty| {
+        let mut out = vec![];
+        write_export(&mut out, problems.into_iter(), format, pretty).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    let json = export(
+        vec![exported(Some(vec!["Array".into()])), exported(None)],
+        ExportFormat::Json,
+        false,
+    );
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value[0]["slug"], "two-sum");
+    assert_eq!(value[0]["tags"], serde_json::json!(["Array"]));
+    assert_eq!(value[1]["tags"], serde_json::Value::Null);
+    assert_eq!(json.lines().count(), 1);
+
+    let mut keys: Vec<_> = value[0].as_object().unwrap().keys().collect();
+    let schema = export_schema();
+    let mut required: Vec<_> = schema["required"].as_array().unwrap().iter().collect();
+    keys.sort();
+    required.sort_by_key(|key| key.as_str());
+    assert_eq!(keys, required);
+
+    let pretty = export(
+        vec![exported(None), exported(None)],
+        ExportFormat::Json,
+        true,
+    );
+    let value: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+    assert_eq!(value.as_array().unwrap().len(), 2);
+    assert_eq!(export(vec![], ExportFormat::Json, true), "[\n]\n");
+
+    let ndjson = export(
+        vec![exported(None), exported(None)],
+        ExportFormat::Ndjson,
+        true,
+    );
+    assert_eq!(ndjson.lines().count(), 2);
+    for line in ndjson.lines() {
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(line).unwrap()["id"],
+            1
+        );
+    }
+}
+
+#[test]
+fn test_merge_categories() {
+    let list = |category: &str, ids: &[usize]| {
+        let pairs = ids
+            .iter()
+            .map(|&id| stat_pair(id, &format!("Problem {}", id), 1, 2))
+            .collect();
+        list_response(category, pairs)
+    };
+
+    let merged = merge_categories(vec![
+        list("algorithms", &[1, 2]),
+        list("database", &[175, 2]),
+    ]);
+    let rows: Vec<_> = merged
+        .iter()
+        .map(|pair| (pair.question_id(), pair.category().unwrap()))
+        .collect();
+    assert_eq!(
+        rows,
+        [(1, "algorithms"), (2, "algorithms"), (175, "database")]
+    );
+}
+
+#[test]
+fn test_problem_info() {
+    let item = stat_pair(1, "Two Sum", 1, 4);
+    let url = "https://leetcode.com/problems/two-sum/";
+
+    let quick = strip_ansi_escapes::strip_str(problem_info(&item, &QuestionDetail::default(), url));
+    assert_eq!(
+        quick,
+        "[1] Two Sum\n\
+         Difficulty  Easy\n\
+         Acceptance  25.0% (1/4)\n\
+         Premium     no\n\
+         URL         https://leetcode.com/problems/two-sum/"
+    );
+
+    let detail: QuestionDetail = serde_json::from_value(serde_json::json!({
+        "likes": 10,
+        "dislikes": 2,
+        "topicTags": [
+            { "name": "Array", "slug": "array" },
+            { "name": "Hash Table", "slug": "hash-table" }
+        ]
+    }))
+    .unwrap();
+    let rich = problem_info(&item, &detail, url);
+    assert!(rich.contains("\nTags        Array, Hash Table\n"));
+    assert!(rich.contains("\nLikes       10 / 2 dislikes\n"));
+}
+
+#[test]
+fn test_use_translation() {
+    let mut detail = QuestionDetail {
+        content: Some("<p>Two Sum</p>".into()),
+        translated_content: Some("<p>两数之和</p>".into()),
+        ..Default::default()
+    };
+    assert!(detail.use_translation());
+    assert_eq!(detail.content.as_deref(), Some("<p>两数之和</p>"));
+
+    let mut detail = QuestionDetail {
+        content: Some("<p>Two Sum</p>".into()),
+        translated_content: Some("".into()),
+        ..Default::default()
+    };
+    assert!(!detail.use_translation());
+    assert_eq!(detail.content.as_deref(), Some("<p>Two Sum</p>"));
+}
+
+#[test]
+fn test_tag_histogram() {
+    let tags = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+    let counts = tag_histogram(&[
+        tags(&["Array", "Hash Table"]),
+        tags(&["Array", "Two Pointers"]),
+        tags(&["String"]),
+        tags(&["Array", "String"]),
+    ]);
+    assert_eq!(
+        counts,
+        [
+            ("Array".to_string(), 3),
+            ("String".to_string(), 2),
+            ("Hash Table".to_string(), 1),
+            ("Two Pointers".to_string(), 1),
+        ]
+    );
+    assert_eq!(
+        format_histogram(&counts[..2], 6),
+        "Array     3 ██████\nString    2 ████\n"
+    );
+    assert_eq!(format_histogram(&[], 6), "");
+}
+
+#[test]
+fn test_acceptance_buckets() {
+    let pair = |acs, submitted| stat_pair(1, "Two Sum", acs, submitted);
+    let probs = [
+        pair(1, 10),
+        pair(19, 100),
+        pair(20, 100),
+        pair(55, 100),
+        pair(10, 10),
+        pair(0, 0),
+    ];
+
+    let buckets = AcceptanceBuckets::new(&probs, 20);
+    assert_eq!(buckets.counts, [2, 1, 1, 0, 1]);
+    assert_eq!(
+        format_histogram(&buckets.ranges(), 4),
+        "0-20%      2 ████\n20-40%     1 ██\n40-60%     1 ██\n60-80%     0 \n80-100%    1 ██\n"
+    );
+
+    let buckets = AcceptanceBuckets::new(&probs, 30);
+    assert_eq!(buckets.counts, [3, 1, 0, 1]);
+    assert_eq!(buckets.ranges()[3], ("90-100%".to_string(), 1));
+}
+
+#[test]
+fn test_check_paid_only() {
+    let withheld: QuestionDetail = serde_json::from_value(serde_json::json!({
+        "content": null,
+        "codeDefinition": null,
+        "sampleTestCase": "",
+        "enableRunCode": false,
+        "metaData": null
+    }))
+    .unwrap();
+    assert!(withheld.is_withheld());
+    assert!(matches!(
+        check_paid_only("lru-cache", true, Ok(withheld)),
+        Err(LeetUpError::PaidOnly(ref slug)) if slug == "lru-cache"
+    ));
+    assert!(matches!(
+        check_paid_only(
+            "lru-cache",
+            true,
+            Err(LeetUpError::ProblemNotFound("x".into()))
+        ),
+        Err(LeetUpError::PaidOnly(_))
+    ));
+
+    let free = QuestionDetail {
+        content: Some("<p>Design a cache</p>".into()),
+        code_definition: Some("[]".into()),
+        ..QuestionDetail::default()
+    };
+    assert!(check_paid_only("lru-cache", true, Ok(free)).is_ok());
+    // Free problems with missing content keep failing the usual way.
+    assert!(check_paid_only("two-sum", false, Ok(QuestionDetail::default())).is_ok());
+    assert!(matches!(
+        check_paid_only(
+            "two-sum",
+            false,
+            Err(LeetUpError::ProblemNotFound("two-sum".into()))
+        ),
+        Err(LeetUpError::ProblemNotFound(_))
+    ));
+}
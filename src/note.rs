@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{cmd::create_config_directory, Result};
+
+/// Directory in the leetup directory holding one note per problem.
+const NOTES_DIR: &str = "notes";
+
+/// Markdown notes about problems, stored as `<id>.md` files.
+pub struct Notes {
+    dir: PathBuf,
+}
+
+impl Notes {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Notes {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Notes stored in the leetup directory.
+    pub fn open() -> Result<Self> {
+        Ok(Notes::new(create_config_directory()?.join(NOTES_DIR)))
+    }
+
+    /// Path of the note of problem `id`, whether it exists or not.
+    pub fn path(&self, id: usize) -> PathBuf {
+        self.dir.join(format!("{}.md", id))
+    }
+
+    /// Note of problem `id`, `None` if there is none.
+    pub fn read(&self, id: usize) -> Result<Option<String>> {
+        match fs::read_to_string(self.path(id)) {
+            Ok(note) => Ok(Some(note)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Create the note of problem `id` starting with `title` unless it
+    /// already exists, returning its path.
+    pub fn create(&self, id: usize, title: &str) -> Result<PathBuf> {
+        let path = self.path(id);
+        if !path.exists() {
+            fs::create_dir_all(&self.dir)?;
+            fs::write(&path, format!("# {}. {}\n\n", id, title))?;
+        }
+        Ok(path)
+    }
+
+    /// IDs of the problems with a note.
+    pub fn ids(&self) -> Result<HashSet<usize>> {
+        if !self.dir.is_dir() {
+            return Ok(HashSet::new());
+        }
+        let mut ids = HashSet::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "md") {
+                continue;
+            }
+            if let Some(id) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse().ok())
+            {
+                ids.insert(id);
+            }
+        }
+        Ok(ids)
+    }
+}
+
+#[test]
+fn test_notes() {
+    let dir = tempfile::tempdir().unwrap();
+    let notes = Notes::new(dir.path().join(NOTES_DIR));
+    assert!(notes.ids().unwrap().is_empty());
+    assert_eq!(notes.read(1).unwrap(), None);
+
+    let path = notes.create(1, "Two Sum").unwrap();
+    assert_eq!(notes.read(1).unwrap().unwrap(), "# 1. Two Sum\n\n");
+    fs::write(&path, "Use a hash map").unwrap();
+    notes.create(1, "Two Sum").unwrap();
+    assert_eq!(notes.read(1).unwrap().unwrap(), "Use a hash map");
+
+    notes.create(42, "Trapping Rain Water").unwrap();
+    fs::write(dir.path().join(NOTES_DIR).join("draft.md"), "").unwrap();
+    fs::write(dir.path().join(NOTES_DIR).join("7.txt"), "").unwrap();
+    assert_eq!(notes.ids().unwrap(), HashSet::from([1, 42]));
+}
@@ -0,0 +1,53 @@
+use std::process::{Command, Stdio};
+
+use anyhow::anyhow;
+
+use crate::{LeetUpError, Result};
+
+/// Show a desktop notification with the platform's notifier.
+pub fn send(title: &str, body: &str) -> Result<()> {
+    let mut command = notification_command(title, body).ok_or_else(|| {
+        LeetUpError::Any(anyhow!(
+            "Desktop notifications are not supported on this platform"
+        ))
+    })?;
+    let status = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(LeetUpError::Any(anyhow!(
+            "Failed to send a desktop notification"
+        )))
+    }
+}
+
+/// `osascript` on macOS and `notify-send` on other Unix systems.
+fn notification_command(title: &str, body: &str) -> Option<Command> {
+    if cfg!(target_os = "macos") {
+        let script = format!("display notification {:?} with title {:?}", body, title);
+        let mut command = Command::new("osascript");
+        command.args(["-e", &script]);
+        Some(command)
+    } else if cfg!(unix) {
+        let mut command = Command::new("notify-send");
+        command.args([title, body]);
+        Some(command)
+    } else {
+        None
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn test_notification_command() {
+    let command = notification_command("New daily challenge", "1. Two Sum").unwrap();
+    assert_eq!(command.get_program(), "notify-send");
+    assert_eq!(
+        command.get_args().collect::<Vec<_>>(),
+        ["New daily challenge", "1. Two Sum"]
+    );
+}
@@ -0,0 +1,56 @@
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use log::warn;
+use terminal_size::{terminal_size, Height};
+
+use crate::Result;
+
+/// Pager used when `$PAGER` is not set, `-R` keeps colors.
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Print `text`, through `$PAGER` if enabled and it doesn't fit the terminal.
+///
+/// Pipes and redirects never get paged.
+pub fn page(text: &str, enabled: bool) -> Result<()> {
+    if enabled && io::stdout().is_terminal() && !fits_terminal(text) {
+        let pager = env::var("PAGER")
+            .ok()
+            .filter(|pager| !pager.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+        match run_pager(&pager, text) {
+            Ok(()) => return Ok(()),
+            Err(e) => warn!("Pager `{}` failed: {}", pager, e),
+        }
+    }
+    print!("{}", text);
+
+    Ok(())
+}
+
+fn fits_terminal(text: &str) -> bool {
+    match terminal_size() {
+        Some((_, Height(height))) => text.lines().count() < height as usize,
+        None => true,
+    }
+}
+
+fn run_pager(pager: &str, text: &str) -> io::Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(pager)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager before reading everything.
+        if let Err(e) = stdin.write_all(text.as_bytes()) {
+            if e.kind() != io::ErrorKind::BrokenPipe {
+                return Err(e);
+            }
+        }
+    }
+    child.wait()?;
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)]
 mod printer;
 mod submit_execution_printer;
 mod test_execution_printer;
@@ -1,8 +1,8 @@
-use crate::model::SubmissionResponse;
+use crate::{color, model::SubmissionResponse};
 
-pub(crate) const NEW_LINE: &'static str = "\n";
-pub(crate) const TEXT_BOLD_ON: &'static str = "\x1b[1m";
-pub(crate) const TEXT_BOLD_OFF: &'static str = "\x1b[m";
+pub(crate) const NEW_LINE: &str = "\n";
+pub(crate) const TEXT_BOLD_ON: &str = "\x1b[1m";
+pub(crate) const TEXT_BOLD_OFF: &str = "\x1b[m";
 
 pub trait Printer {
     fn print(&self) {
@@ -26,6 +26,9 @@ pub mod decorator {
     use super::*;
 
     pub fn bold_text(s: &str) -> String {
+        if !color::is_enabled() {
+            return s.to_string();
+        }
         format!("{}{}{}", s, TEXT_BOLD_ON, TEXT_BOLD_OFF)
     }
 }
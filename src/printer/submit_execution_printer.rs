@@ -1,4 +1,4 @@
-use colci::Color;
+use crate::color::Color;
 
 use crate::model::ExecutionErrorResponse;
 use crate::printer::{decorator::bold_text, Printer, NEW_LINE};
@@ -64,7 +64,7 @@ impl SubmitExecutionResult {
         buffer.push_str(&bold_text(
             &Color::Red(&format!(
                 "\n{} Wrong Answer: ({})\n\n",
-                Icon::_No.to_string(),
+                Icon::_No,
                 self.total_cases_ratio_buffer(&self.submission_response)
             ))
             .make(),
@@ -77,28 +77,25 @@ impl SubmitExecutionResult {
 
     fn last_test_case_buffer(&self) -> String {
         let mut buffer = String::new();
-        match (
+        if let (
+            Some(Either::String(input)),
+            Some(Either::String(ans)),
+            Some(Either::String(exp_ans)),
+        ) = (
             &self.submission_response.input,
             &self.submission_response.code_output,
             &self.submission_response.expected_output,
         ) {
-            (
-                Some(Either::String(input)),
-                Some(Either::String(ans)),
-                Some(Either::String(exp_ans)),
-            ) => {
-                let mut test_case = String::new();
-                test_case.push_str(&Color::Red("Last test case:\n").make());
-                test_case.push_str(&format!(
-                    "\tInput: \n\t\t{}\n",
-                    input.replace('\n', "\n\t\t")
-                ));
-                test_case.push_str(&format!("\n\tOutput: {}\n", ans));
-                test_case.push_str(&format!("\tExpected: {}\n\n", exp_ans));
+            let mut test_case = String::new();
+            test_case.push_str(&Color::Red("Last test case:\n").make());
+            test_case.push_str(&format!(
+                "\tInput: \n\t\t{}\n",
+                input.replace('\n', "\n\t\t")
+            ));
+            test_case.push_str(&format!("\n\tOutput: {}\n", ans));
+            test_case.push_str(&format!("\tExpected: {}\n\n", exp_ans));
 
-                buffer.push_str(test_case.as_str());
-            }
-            _ => {}
+            buffer.push_str(test_case.as_str());
         }
 
         buffer
@@ -109,7 +106,7 @@ impl SubmitExecutionResult {
         buffer.push_str(&bold_text(
             &Color::Green(&format!(
                 "{} Accepted: ({})\n\n",
-                Icon::Yes.to_string(),
+                Icon::Yes,
                 self.total_cases_ratio_buffer(&self.submission_response)
             ))
             .make(),
@@ -134,7 +131,7 @@ impl SubmitExecutionResult {
             .runtime_percentile
             .unwrap_or(0.0)
             .to_string();
-        let metas = vec![
+        let metas = [
             "Memory: ".to_string() + self.submission_response.status_memory.as_str(),
             "Memory %ile: ".to_string() + memory_percentile.as_str(),
             "Runtime: ".to_string() + self.submission_response.status_runtime.as_str(),
@@ -186,12 +183,12 @@ mod tests {
                     )
         .unwrap();
 
-        let response = from_value::<SubmissionResponse>(json_value).unwrap().into();
+        let response = from_value::<SubmissionResponse>(json_value).unwrap();
 
         let result = SubmitExecutionResult::new(response);
         result.print();
         // TODO implement snapshot testing
-        assert!(1 == 1);
+        assert!(!result.buffer().is_empty());
     }
 
     #[test]
@@ -226,12 +223,12 @@ mod tests {
         )
         .unwrap();
 
-        let response = from_value::<SubmissionResponse>(json_value).unwrap().into();
+        let response = from_value::<SubmissionResponse>(json_value).unwrap();
 
         let result = SubmitExecutionResult::new(response);
         result.print();
         // TODO implement snapshot testing
-        assert!(1 == 1);
+        assert!(!result.buffer().is_empty());
     }
 
     #[test]
@@ -269,11 +266,11 @@ r#"{
         )
         .unwrap();
 
-        let response = from_value::<SubmissionResponse>(json_value).unwrap().into();
+        let response = from_value::<SubmissionResponse>(json_value).unwrap();
 
         let result = SubmitExecutionResult::new(response);
         result.print();
         // TODO implement snapshot testing
-        assert!(1 == 1);
+        assert!(!result.buffer().is_empty());
     }
 }
@@ -1,4 +1,4 @@
-use colci::Color;
+use crate::color::Color;
 
 use crate::model::ExecutionErrorResponse;
 use crate::printer::{decorator::bold_text, Printer, NEW_LINE};
@@ -66,7 +66,7 @@ impl TestExecutionResult {
         buffer.push_str(&bold_text(
             &Color::Red(&format!(
                 "\n{} Wrong Answer: ({})\n\n",
-                Icon::_No.to_string(),
+                Icon::_No,
                 self.total_cases_ratio_buffer(&self.submission_response)
             ))
             .make(),
@@ -77,9 +77,9 @@ impl TestExecutionResult {
         buffer
     }
 
-    fn test_cases_buffer(&self) -> String {
-        let mut buffer = String::new();
-        // combine test_data, code_answer & expected_code_answer
+    /// Input, output and expected output of each test case, combining
+    /// test_data, code_answer & expected_code_answer.
+    fn test_cases(&self) -> Vec<(&[String], &String, &String)> {
         match (
             &self.test_data,
             &self.submission_response.code_answer,
@@ -89,45 +89,95 @@ impl TestExecutionResult {
                 Either::Sequence(input_seq),
                 Some(Either::Sequence(ans_seq)),
                 Some(Either::Sequence(exp_ans_seq)),
-            ) => {
-                let chunk_size = input_seq.len() / ans_seq.len();
-                let input_chunks: Vec<Vec<String>> = input_seq
+            ) if !ans_seq.is_empty() => {
+                let chunk_size = (input_seq.len() / ans_seq.len()).max(1);
+                input_seq
                     .chunks(chunk_size)
-                    .map(|chunk| chunk.to_vec())
-                    .collect();
-                for (i, ((input, ans), exp_ans)) in input_chunks
-                    .iter()
                     .zip(ans_seq)
                     .zip(exp_ans_seq)
-                    .enumerate()
-                {
-                    let mut test_case = String::new();
-                    let is_correct = ans.eq(exp_ans);
-                    let colored_case = if is_correct {
-                        Color::Green(&format!("{} Case {}:\n", Icon::Yes.to_string(), i + 1)).make()
-                    } else {
-                        Color::Red(&format!("{} Case {}:\n", Icon::_No.to_string(), i + 1)).make()
-                    };
-                    test_case.push_str(&colored_case);
-                    test_case.push_str(&format!("\tInput: \n\t\t{}\n", input.join("\n\t\t")));
-                    test_case.push_str(&format!("\n\tOutput: {}\n", ans));
-                    test_case.push_str(&format!("\tExpected: {}\n\n", exp_ans));
-
-                    buffer.push_str(test_case.as_str());
-                }
+                    .map(|((input, ans), exp_ans)| (input, ans, exp_ans))
+                    .collect()
             }
-            _ => {}
+            _ => vec![],
+        }
+    }
+
+    fn test_cases_buffer(&self) -> String {
+        let mut buffer = String::new();
+        for (i, (input, ans, exp_ans)) in self.test_cases().into_iter().enumerate() {
+            let mut test_case = String::new();
+            let is_correct = ans.eq(exp_ans);
+            let colored_case = if is_correct {
+                Color::Green(&format!("{} Case {}:\n", Icon::Yes, i + 1)).make()
+            } else {
+                Color::Red(&format!("{} Case {}:\n", Icon::_No, i + 1)).make()
+            };
+            test_case.push_str(&colored_case);
+            test_case.push_str(&format!("\tInput: \n\t\t{}\n", input.join("\n\t\t")));
+            test_case.push_str(NEW_LINE);
+            test_case.push_str(&answers_buffer(ans, exp_ans));
+            test_case.push_str(NEW_LINE);
+
+            buffer.push_str(test_case.as_str());
         }
 
         buffer
     }
 
+    /// Plain text record of a failed run for the test log, headed by
+    /// `time`. `None` if every case passed.
+    ///
+    /// Errors are logged with their message, wrong answers with the input,
+    /// output and expected output of each failing case.
+    pub fn failure_log(&self, time: &str) -> Option<String> {
+        let response = &self.submission_response;
+        let mut log = String::new();
+        if response.has_compile_error() || response.has_runtime_error() {
+            log.push_str(&format!("=== {} {}\n", time, response.status_msg));
+            let error = response
+                .full_compile_error
+                .as_deref()
+                .or(response.compile_error.as_deref())
+                .unwrap_or_default();
+            if !error.is_empty() {
+                log.push_str(&format!("{}\n", error.trim_end()));
+            }
+            if let Some(ref input) = response.last_test_case {
+                log.push_str(&format!("  Input:    {}\n", input));
+            }
+        } else {
+            let failed: Vec<_> = self
+                .test_cases()
+                .into_iter()
+                .enumerate()
+                .filter(|(_, (_, ans, exp_ans))| ans != exp_ans)
+                .collect();
+            if failed.is_empty() {
+                return None;
+            }
+            log.push_str(&format!(
+                "=== {} Wrong Answer ({})\n",
+                time,
+                self.total_cases_ratio_buffer(response)
+            ));
+            for (i, (input, ans, exp_ans)) in failed {
+                log.push_str(&format!("Case {}\n", i + 1));
+                log.push_str(&format!("  Input:    {}\n", input.join("\n            ")));
+                log.push_str(&format!("  Output:   {}\n", ans));
+                log.push_str(&format!("  Expected: {}\n", exp_ans));
+            }
+        }
+        log.push('\n');
+
+        Some(log)
+    }
+
     fn success_buffer(&self) -> String {
         let mut buffer = String::new();
         buffer.push_str(&bold_text(
             &Color::Green(&format!(
                 "{} Accepted: ({})\n\n",
-                Icon::Yes.to_string(),
+                Icon::Yes,
                 self.total_cases_ratio_buffer(&self.submission_response)
             ))
             .make(),
@@ -159,7 +209,7 @@ impl TestExecutionResult {
             self.submission_response.status_msg.as_str(),
             testcases.as_str()
         );
-        let metas = vec![
+        let metas = [
             accepted_meta,
             "Memory: ".to_string() + self.submission_response.status_memory.as_str(),
             "Memory %ile: ".to_string() + memory_percentile.as_str(),
@@ -172,9 +222,30 @@ impl TestExecutionResult {
     }
 }
 
+/// Output and expected output side by side, the output in red when it
+/// doesn't match.
+fn answers_buffer(ans: &str, exp_ans: &str) -> String {
+    let width = ans.chars().count().max("Output".len());
+    let output = format!("{:<width$}", ans, width = width);
+    let output = if ans == exp_ans {
+        output
+    } else {
+        Color::Red(&output).make()
+    };
+
+    format!(
+        "\t{:<width$} | Expected\n\t{} | {}\n",
+        "Output",
+        output,
+        exp_ans,
+        width = width
+    )
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Printer, TestExecutionResult};
+    use super::{answers_buffer, Printer, TestExecutionResult};
+    use crate::color::Color;
     use crate::{model::SubmissionResponse, Either};
     use serde_json::from_value;
 
@@ -258,12 +329,12 @@ mod tests {
         )
         .unwrap();
 
-        let response = from_value::<SubmissionResponse>(json_value).unwrap().into();
+        let response = from_value::<SubmissionResponse>(json_value).unwrap();
 
         let result = TestExecutionResult::new(test_data, response);
         result.print();
         // TODO implement snapshot testing
-        assert!(1 == 1);
+        assert!(!result.buffer().is_empty());
     }
 
     #[test]
@@ -346,12 +417,12 @@ mod tests {
         )
         .unwrap();
 
-        let response = from_value::<SubmissionResponse>(json_value).unwrap().into();
+        let response = from_value::<SubmissionResponse>(json_value).unwrap();
 
         let result = TestExecutionResult::new(test_data, response);
         result.print();
         // TODO implement snapshot testing
-        assert!(1 == 1);
+        assert!(!result.buffer().is_empty());
     }
 
     #[test]
@@ -369,12 +440,12 @@ r#"{"status_code": 10, "lang": "rust", "run_success": true, "status_runtime": "0
         )
         .unwrap();
 
-        let response = from_value::<SubmissionResponse>(json_value).unwrap().into();
+        let response = from_value::<SubmissionResponse>(json_value).unwrap();
 
         let result = TestExecutionResult::new(test_data, response);
         result.print();
         // TODO implement snapshot testing
-        assert!(1 == 1);
+        assert!(!result.buffer().is_empty());
     }
 
     #[test]
@@ -392,11 +463,64 @@ r#"{"status_code": 10, "lang": "rust", "run_success": true, "status_runtime": "0
         )
         .unwrap();
 
-        let response = from_value::<SubmissionResponse>(json_value).unwrap().into();
+        let response = from_value::<SubmissionResponse>(json_value).unwrap();
 
         let result = TestExecutionResult::new(test_data, response);
         result.print();
         // TODO implement snapshot testing
-        assert!(1 == 1);
+        assert!(!result.buffer().is_empty());
+    }
+
+    #[test]
+    fn answers_side_by_side() {
+        assert_eq!(
+            answers_buffer("true", "true"),
+            "\tOutput | Expected\n\ttrue   | true\n"
+        );
+        assert_eq!(
+            answers_buffer("[[-1,-1,2]]", "[[-1,-1,2],[-1,0,1]]"),
+            format!(
+                "\tOutput      | Expected\n\t{} | [[-1,-1,2],[-1,0,1]]\n",
+                Color::Red("[[-1,-1,2]]").make()
+            )
+        );
+    }
+
+    #[test]
+    fn failure_log() {
+        let test_data = || {
+            Either::Sequence(vec![
+                "aa".to_owned(),
+                "a".to_owned(),
+                "aa".to_owned(),
+                "a*".to_owned(),
+            ])
+        };
+        let response = |answers: [&str; 2]| -> SubmissionResponse {
+            from_value(serde_json::json!({
+                "lang": "rust",
+                "pretty_lang": "Rust",
+                "run_success": true,
+                "code_answer": answers,
+                "expected_code_answer": ["false", "true"],
+                "total_correct": 1,
+                "total_testcases": 2,
+                "status_memory": "2 MB",
+                "status_msg": "Accepted",
+                "status_runtime": "1 ms",
+                "submission_id": "runcode_1"
+            }))
+            .unwrap()
+        };
+
+        let result = TestExecutionResult::new(test_data(), response(["false", "false"]));
+        assert_eq!(
+            result.failure_log("2024-01-02 03:04").unwrap(),
+            "=== 2024-01-02 03:04 Wrong Answer (1/2)\n\
+             Case 2\n  Input:    aa\n            a*\n  Output:   false\n  Expected: true\n\n"
+        );
+
+        let result = TestExecutionResult::new(test_data(), response(["false", "true"]));
+        assert_eq!(result.failure_log("2024-01-02 03:04"), None);
     }
 }
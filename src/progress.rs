@@ -0,0 +1,38 @@
+use std::io::{self, IsTerminal};
+use std::time::Duration;
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// Whether progress can be drawn, only when both stdout and stderr are
+/// terminals so piped output and logs stay clean.
+fn is_shown() -> bool {
+    io::stdout().is_terminal() && io::stderr().is_terminal()
+}
+
+/// Spinner on stderr for a single long request.
+pub fn spinner(message: &'static str) -> ProgressBar {
+    if !is_shown() {
+        return ProgressBar::hidden();
+    }
+    let spinner = ProgressBar::new_spinner().with_message(message);
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    spinner
+}
+
+/// Bar on stderr counting `len` items of a batch, with percent and ETA.
+pub fn bar(len: usize, message: &'static str) -> ProgressBar {
+    if !is_shown() || len == 0 {
+        return ProgressBar::hidden();
+    }
+    let style = ProgressStyle::with_template("{msg} [{bar:30}] {pos}/{len} {percent}% ETA {eta}")
+        .expect("Valid progress template")
+        .progress_chars("=> ");
+    ProgressBar::new(len as u64)
+        .with_style(style)
+        .with_message(message)
+}
+
+#[test]
+fn test_empty_bar_is_hidden() {
+    assert!(bar(0, "Fetching").is_hidden());
+}
@@ -1,6 +1,6 @@
 use std::io::{BufWriter, Write};
 
-use colci::Color;
+use crate::color::Color;
 
 use crate::{
     service::{ServiceProvider, Session},
@@ -25,7 +25,5 @@ pub async fn cookie_login<'a, P: ServiceProvider<'a>>(_provider: &P) -> Result<S
     csrf = csrf.trim().to_string();
     lc_session = lc_session.trim().to_string();
 
-    println!("{}", Color::Green("User logged in!").make());
-
     Ok(Session::new(lc_session.to_string(), csrf.to_string()))
 }
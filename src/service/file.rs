@@ -6,7 +6,7 @@ use std::str::FromStr;
 
 use log::*;
 
-use crate::model::Problem;
+use crate::model::{DifficultyType, Problem};
 use crate::{template::Pattern, LeetUpError, Result};
 
 impl FromStr for Problem {
@@ -31,6 +31,7 @@ impl FromStr for Problem {
             lang,
             link,
             typed_code: None,
+            difficulty: DifficultyType::Unknown,
         })
     }
 }
@@ -0,0 +1,144 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::{LeetUpError, Result};
+
+/// How often and how long to poll the judge for a verdict.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PollSettings {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+/// Call `check` every `settings.interval` until it returns a finished
+/// result, i.e. one whose `state` is `SUCCESS`.
+///
+/// Gives up with `JudgeTimeout` once waiting for another poll would exceed
+/// `settings.timeout`, so a stuck judge doesn't hang the CLI forever. The
+/// judge is always polled at least once. `cancelled` is checked before each
+/// poll to stop on Ctrl-C.
+pub async fn poll<F, Fut>(
+    id: &str,
+    settings: PollSettings,
+    cancelled: impl Fn() -> bool,
+    mut check: F,
+) -> Result<Value>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Value>>,
+{
+    let start = Instant::now();
+    loop {
+        if cancelled() {
+            return Err(LeetUpError::Cancelled);
+        }
+        let response = check().await?;
+        if response["state"] == "SUCCESS" {
+            return Ok(response);
+        }
+        let next = start.elapsed().checked_add(settings.interval);
+        if next.is_none_or(|next| next > settings.timeout) {
+            return Err(LeetUpError::JudgeTimeout(id.to_string()));
+        }
+        tokio::time::sleep(settings.interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use serde_json::json;
+
+    use super::*;
+
+    const FAST: PollSettings = PollSettings {
+        interval: Duration::from_millis(1),
+        timeout: Duration::from_secs(5),
+    };
+
+    /// Judge answering `PENDING` until its `polls`th poll.
+    fn judge(
+        polls: usize,
+        calls: &Cell<usize>,
+    ) -> impl FnMut() -> std::future::Ready<Result<Value>> + '_ {
+        move || {
+            calls.set(calls.get() + 1);
+            let state = if calls.get() >= polls {
+                "SUCCESS"
+            } else {
+                "PENDING"
+            };
+            std::future::ready(Ok(json!({ "state": state })))
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_once_finished() {
+        let calls = Cell::new(0);
+        let response = poll("1", FAST, || false, judge(3, &calls)).await.unwrap();
+        assert_eq!(response["state"], "SUCCESS");
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn times_out_with_the_submission_id() {
+        let calls = Cell::new(0);
+        let settings = PollSettings {
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(35),
+        };
+        let err = poll("42", settings, || false, judge(usize::MAX, &calls))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LeetUpError::JudgeTimeout(ref id) if id == "42"));
+        assert!((2..=4).contains(&calls.get()), "{} polls", calls.get());
+    }
+
+    #[tokio::test]
+    async fn polls_once_without_timeout() {
+        let calls = Cell::new(0);
+        let settings = PollSettings {
+            interval: Duration::from_secs(1),
+            timeout: Duration::ZERO,
+        };
+        let err = poll("1", settings, || false, judge(2, &calls))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LeetUpError::JudgeTimeout(_)));
+        assert_eq!(calls.get(), 1);
+
+        let calls = Cell::new(0);
+        let settings = PollSettings {
+            interval: Duration::MAX,
+            timeout: Duration::from_secs(30),
+        };
+        let err = poll("1", settings, || false, judge(2, &calls))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LeetUpError::JudgeTimeout(_)));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn stops_when_cancelled_or_failing() {
+        let calls = Cell::new(0);
+        let err = poll("1", FAST, || calls.get() == 2, judge(5, &calls))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LeetUpError::Cancelled));
+        assert_eq!(calls.get(), 2);
+
+        let err = poll(
+            "1",
+            FAST,
+            || false,
+            || std::future::ready(Err(LeetUpError::SessionExpired)),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, LeetUpError::SessionExpired));
+    }
+}
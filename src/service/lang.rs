@@ -1,6 +1,5 @@
 use std::str::FromStr;
 
-use anyhow::anyhow;
 use serde::{de, Deserialize};
 
 use crate::LeetUpError;
@@ -31,6 +30,25 @@ pub enum Comment {
     MySQL(CommentStyle, Option<CommentStyle>),
 }
 
+/// Names accepted by `Lang::from_str`, aliases excluded.
+pub const SUPPORTED_LANGS: &[&str] = &[
+    "rust",
+    "java",
+    "javascript",
+    "python3",
+    "mysql",
+    "cpp",
+    "ruby",
+    "c",
+    "csharp",
+    "golang",
+    "php",
+    "kotlin",
+    "scala",
+    "swift",
+    "typescript",
+];
+
 /// Represent different languages supported by a Service provider.
 #[derive(Debug, Clone)]
 pub enum Lang {
@@ -168,14 +186,31 @@ impl FromStr for Lang {
                 extension: "ts".into(),
                 comment: c_comment,
             })),
-            _ => Err(LeetUpError::Any(anyhow!("Language not supported!"))),
+            _ => Err(LeetUpError::LangNotSupported(
+                s.to_string(),
+                SUPPORTED_LANGS.join(", "),
+            )),
         }
     }
 }
 
 impl Lang {
     pub fn info(&self) -> LangInfo {
-        match self.clone() {
+        self.info_ref().clone()
+    }
+
+    /// LeetCode's internal name for this language, e.g. `python3` or `golang`.
+    pub fn slug(&self) -> &str {
+        &self.info_ref().name
+    }
+
+    /// File extension used for generated sources, without the dot.
+    pub fn extension(&self) -> &str {
+        &self.info_ref().extension
+    }
+
+    fn info_ref(&self) -> &LangInfo {
+        match self {
             Lang::Rust(info) => info,
             Lang::Java(info) => info,
             Lang::Javascript(info) => info,
@@ -204,3 +239,22 @@ impl<'de> Deserialize<'de> for Lang {
         Lang::from_str(&s).map_err(de::Error::custom)
     }
 }
+
+#[test]
+fn test_lang_slug_and_extension() {
+    let go = Lang::from_str("go").unwrap();
+    assert_eq!(go.slug(), "golang");
+    assert_eq!(go.extension(), "go");
+
+    let py = Lang::from_str("py").unwrap();
+    assert_eq!(py.slug(), "python3");
+    assert_eq!(py.extension(), "py");
+
+    for name in SUPPORTED_LANGS {
+        assert_eq!(Lang::from_str(name).unwrap().slug(), *name);
+    }
+
+    let err = Lang::from_str("cobol").unwrap_err().to_string();
+    assert!(err.contains("cobol"));
+    assert!(err.contains("rust, java"));
+}
@@ -1,35 +1,65 @@
 use std::cmp::Ord;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs::{self, File};
-use std::io::{prelude::*, stdin};
+use std::io::{self, prelude::*, stdin, BufWriter, IsTerminal};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 
+use crate::color::Color;
 use anyhow::anyhow;
 use async_trait::async_trait;
-use colci::Color;
-use html2text::from_read;
+use futures::stream::{self, StreamExt};
 use leetup_cache::kvstore::KvStore;
-use log::{debug, info};
+use log::{debug, info, warn};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use reqwest::header::{self, HeaderMap, HeaderValue};
 use serde_json::{json, Value};
 
+use crate::history::{format_timestamp, History, HistoryEntry};
 use crate::model::{
-    CodeDefinition, Problem, ProblemInfo, ProblemInfoSeq, StatStatusPair, SubmissionResponse,
-    TopicTagQuestion,
+    apply_queries, check_format, check_paid_only, export_schema, format_histogram, format_problem,
+    keyword_rank, matches_keyword, merge_categories, parse_fields, problem_info, problem_row,
+    tag_histogram, title_width, with_ordering, write_export, AcceptanceBuckets, CodeDefinition,
+    CompanyTagData, DailyChallenge, DailyChallengeData, DifficultyType, ExportedProblem,
+    FavoritesResponse, ListResponse, Problem, ProblemInfo, ProblemInfoSeq, ProblemStats,
+    ProblemSummary, QuestionData, QuestionDetail, SimilarQuestion, StatStatusPair, SubmissionDump,
+    SubmissionList, SubmissionResponse, TopicTagData, TopicTagQuestion,
 };
 use crate::printer::SubmitExecutionResult;
-use crate::template::parse_code;
+use crate::template::{
+    check_filename_template, extract_marked_code, parse_code, render_filename, replace_marked_code,
+    DEFAULT_FILENAME_TEMPLATE,
+};
 use crate::{
-    client::RemoteClient,
+    browser,
+    client::{Fetched, RemoteClient},
     cmd::{self, List, OrderBy, Query, User},
+    diff, editor, html, interrupt,
+    last_pick::LastPick,
+    note::Notes,
+    notify,
     printer::{Printer, TestExecutionResult},
-    service::{self, auth, CacheKey, Comment, CommentStyle, LangInfo, ServiceProvider, Session},
+    progress,
+    service::{
+        self, auth, cache_namespace, content_hash,
+        judge::{self, PollSettings},
+        unix_now, CacheKey, Comment, CommentStyle, Lang, LangInfo, ServiceProvider, Session,
+    },
+    snapshot::Snapshot,
     template::{InjectPosition, Pattern},
     Config, Either, LeetUpError, Result,
 };
 
+/// Number of problems fetched at once by `pick --batch`.
+const BATCH_CONCURRENCY: usize = 4;
+
+/// Submissions fetched per request when looking for the last accepted one.
+const SUBMISSIONS_PAGE_SIZE: usize = 20;
+
+/// Environment variable used to seed the random problem picker.
+const RANDOM_SEED_ENV: &str = "LEETUP_RANDOM_SEED";
+
 /// Leetcode holds all attributes required to implement ServiceProvider trait.
 pub struct Leetcode<'a> {
     /// Store user session
@@ -43,8 +73,11 @@ pub struct Leetcode<'a> {
     /// Provides caching mechanism for OJ(Online Judge).
     cache: KvStore,
 
-    /// Service provider name
-    name: &'a str,
+    /// Prefix of cached problem keys, per site and user.
+    cache_namespace: String,
+
+    /// Problem list of the cache namespace.
+    snapshot: Snapshot,
 
     remote_client: RemoteClient<'a>,
 }
@@ -61,280 +94,1571 @@ impl<'a> ServiceProvider<'a> for Leetcode<'a> {
 
     /// Fetch all problems
     ///
-    /// Use cache wherever necessary
+    /// Use cache wherever necessary, unless it is older than `cache_ttl`.
     async fn fetch_all_problems(&mut self) -> Result<Value> {
         let problems_res: Value;
-        if let Some(ref val) = self.cache.get(CacheKey::Problems.into())? {
-            debug!("Fetching problems from cache...");
+        let cached = if self.config.offline {
+            Some(self.snapshot.read()?.ok_or(LeetUpError::NoCachedProblems)?)
+        } else if self.is_cache_fresh(CacheKey::ProblemsFetchedAt)? {
+            self.snapshot.read()?
+        } else {
+            None
+        };
+        if let Some(ref val) = cached {
+            info!("Problems cache hit");
             problems_res = serde_json::from_str::<Value>(val)?;
         } else {
-            let url = &self.config.urls.problems_all;
-            let session = self.session();
-            problems_res = self
+            info!("Problems cache miss");
+            // Only revalidate when the cached list is still there, e.g. not
+            // after `clear_problems_cache`.
+            let has_cached = self.snapshot.exists();
+            let etag = if has_cached {
+                self.cache.get(self.cache_key(CacheKey::ProblemsETag))?
+            } else {
+                None
+            };
+            let spinner = progress::spinner("Fetching problems");
+            let fetched = self
                 .remote_client
-                .get(url, None, session)
-                .await?
-                .json::<Value>()
-                .await
-                .map_err(LeetUpError::Reqwest)?;
-            let res_serialized = serde_json::to_string(&problems_res)?;
-            self.cache.set(CacheKey::Problems.into(), res_serialized)?;
+                .get_if_changed(
+                    &self.config.urls.problems("all"),
+                    self.session(),
+                    etag.as_deref(),
+                )
+                .await;
+            spinner.finish_and_clear();
+            let body = match fetched? {
+                Fetched::NotModified => {
+                    info!("Problems not modified");
+                    self.snapshot.read()?.ok_or(LeetUpError::NoCachedProblems)?
+                }
+                Fetched::Modified { body, etag } => {
+                    let hash = content_hash(&body);
+                    let cached_hash = if has_cached {
+                        self.cache.get(self.cache_key(CacheKey::ProblemsHash))?
+                    } else {
+                        None
+                    };
+                    if cached_hash.as_ref() == Some(&hash) {
+                        info!("Problems unchanged");
+                    } else {
+                        self.snapshot.write(&body)?;
+                        self.cache
+                            .set(self.cache_key(CacheKey::ProblemsHash), hash)?;
+                    }
+                    match etag {
+                        Some(etag) => self
+                            .cache
+                            .set(self.cache_key(CacheKey::ProblemsETag), etag)?,
+                        None => {
+                            let _ = self.cache.remove(self.cache_key(CacheKey::ProblemsETag));
+                        }
+                    }
+                    body
+                }
+            };
+            problems_res = serde_json::from_str::<Value>(&body).map_err(|e| {
+                warn!(
+                    "Unexpected response from {}: {}",
+                    self.config.urls.problems("all"),
+                    e
+                );
+                e
+            })?;
+            self.cache.set(
+                self.cache_key(CacheKey::ProblemsFetchedAt),
+                unix_now().to_string(),
+            )?;
         }
 
         Ok(problems_res)
     }
 
     async fn list_problems(&mut self, list: List) -> Result<()> {
-        if !self.is_user_logged_in() {
-            print!(
-                "{}",
-                Color::Red("You need to login to list problems").make()
-            );
+        if let Some(ref format) = list.format {
+            check_format(format)?;
+        }
+        let fields = list.fields.as_deref().map(parse_fields).transpose()?;
+
+        let mut probs = self.query_problems(&list).await?;
+        if list.count {
+            println!("{}", probs.len());
+            if probs.is_empty() {
+                return Err(LeetUpError::NoMatches);
+            }
             return Ok(());
         }
+        if let Some(limit) = list.limit.filter(|limit| *limit > 0) {
+            probs.truncate(limit);
+        }
 
-        let problems_res = self.fetch_all_problems().await?;
+        if list.only_ids {
+            for prob in &probs {
+                println!("{}", prob.question_id());
+            }
+            return Ok(());
+        }
+        if let Some(ref format) = list.format {
+            for prob in &probs {
+                println!("{}", format_problem(format, prob.as_ref()));
+            }
+            return Ok(());
+        }
+
+        Leetcode::print_list(&probs, list.output, fields.as_deref(), !list.no_pager)?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tui")]
+    async fn browse_problems(&mut self, list: List) -> Result<()> {
+        if !io::stdout().is_terminal() || !stdin().is_terminal() {
+            return self.list_problems(list).await;
+        }
+
+        let mut probs = self.query_problems(&list).await?;
+        if let Some(limit) = list.limit.filter(|limit| *limit > 0) {
+            probs.truncate(limit);
+        }
+        let id = match crate::browse::browse(&probs)? {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+
+        self.pick_problem(cmd::Pick::for_id(id.to_string(), None))
+            .await
+    }
+
+    async fn random_problem(&mut self, random: cmd::Random) -> Result<()> {
+        let problems = self.fetch_problems().await?;
         let mut probs: ProblemInfoSeq = vec![];
+        for prob in problems {
+            probs.push(Box::new(prob));
+        }
+        let probs = Leetcode::filter_problems(probs, None, random.query.as_deref())?;
 
-        if let Some(ref tag) = list.tag {
-            let tag_questions = self.get_problems_with_topic_tag(tag).await?["data"]["topicTag"]
-                ["questions"]
-                .clone();
-            let problems: Vec<TopicTagQuestion> = serde_json::from_value(tag_questions)?;
-            for prob in problems {
-                probs.push(Box::new(prob));
+        let prob = match probs.choose(&mut random_generator()) {
+            Some(prob) => prob,
+            None => {
+                println!("{}", Color::Yellow("No problem matches the query").make());
+                return Ok(());
             }
-        } else {
-            let problems: Vec<StatStatusPair> =
-                serde_json::from_value(problems_res["stat_status_pairs"].clone())?;
+        };
+        Leetcode::pretty_list(std::slice::from_ref(prob));
+        println!("{}{}/", self.config.urls.problems, prob.slug());
 
-            for prob in problems {
-                probs.push(Box::new(prob));
-            }
+        if random.pick {
+            let pick = cmd::Pick::for_id(prob.question_id().to_string(), random.lang);
+            self.pick_problem(pick).await?;
         }
 
-        if let Some(ref order) = list.order {
-            let orders = OrderBy::from_str(order);
-            probs.sort_by(|a, b| Leetcode::with_ordering(orders.as_slice(), a, b));
-        } else {
-            probs.sort_by(Ord::cmp);
-        }
-
-        if list.query.is_some() || list.keyword.is_some() {
-            let filter_predicate = |o: &Box<dyn ProblemInfo + Send>| {
-                let default_keyword = String::from("");
-                let keyword = list
-                    .keyword
-                    .as_ref()
-                    .unwrap_or(&default_keyword)
-                    .to_ascii_lowercase();
-                let has_keyword = o.question_title().to_lowercase().contains(&keyword);
-
-                return list
-                    .query
-                    .as_ref()
-                    .map(|query| Query::from_str(query))
-                    .map(|queries| Leetcode::apply_queries(&queries, o))
-                    .map(|result| has_keyword && result)
-                    .unwrap_or(has_keyword);
-            };
+        Ok(())
+    }
 
-            Leetcode::pretty_list(
-                &probs
-                    .into_iter()
-                    .filter(filter_predicate)
-                    .collect::<Vec<Box<dyn ProblemInfo + Send + 'static>>>(),
-            );
-        } else {
-            Leetcode::pretty_list(probs.iter());
+    async fn next_problem(&mut self, next: cmd::Next) -> Result<()> {
+        let problems = self.fetch_problems().await?;
+        let mut probs: ProblemInfoSeq = vec![];
+        for prob in problems {
+            probs.push(Box::new(prob));
+        }
+        let probs = Leetcode::filter_problems(probs, None, Some(&next.query))?;
+        let any_matches = !probs.is_empty();
+        let prob = probs
+            .into_iter()
+            .filter(|prob| prob.status().is_none())
+            .min_by_key(|prob| prob.question_id());
+
+        let prob = match prob {
+            Some(prob) => prob,
+            None if any_matches => {
+                println!(
+                    "{}",
+                    Color::Green("Congratulations, you solved every problem matching the query!")
+                        .make()
+                );
+                return Ok(());
+            }
+            None => {
+                println!("{}", Color::Yellow("No problem matches the query").make());
+                return Ok(());
+            }
+        };
+        Leetcode::pretty_list(std::slice::from_ref(&prob));
+
+        let pick = cmd::Pick::for_id(prob.question_id().to_string(), next.lang);
+        self.pick_problem(pick).await
+    }
+
+    async fn daily_problem(&mut self, daily: cmd::Daily) -> Result<()> {
+        if daily.watch {
+            return self.watch_daily(&daily).await;
+        }
+        let daily_challenge = self.fetch_daily().await?;
+        self.print_daily(&daily_challenge);
+        let question = &daily_challenge.question;
+
+        if daily.pick {
+            let pick = cmd::Pick::for_id(question.title_slug.to_owned(), daily.lang);
+            self.pick_problem(pick).await?;
         }
 
         Ok(())
     }
 
     async fn pick_problem(&mut self, pick: cmd::Pick) -> Result<()> {
-        let probs = self.fetch_problems().await?;
-        let urls = &self.config.urls;
         let lang = pick
             .lang
             .as_ref()
             .map(|l| l.info())
             .unwrap_or(self.config.lang.info());
 
-        let problem: Problem = probs
-            .iter()
-            .find(|item| {
-                item.stat.frontend_question_id == pick.id.expect("Expected frontend_question_id")
-            })
-            .map(|item| Problem {
-                id: item.stat.frontend_question_id,
-                link: format!("{}{}/", urls.problems, item.stat.question_title_slug),
-                slug: item.stat.question_title_slug.to_string(),
-                lang: lang.name.to_owned(),
-                typed_code: None,
-            })
-            .expect("Problem with given ID not found");
+        if let Some(ref template) = self.config.filename_template {
+            check_filename_template(template)?;
+        }
+        if let Some(ref batch) = pick.batch {
+            return self
+                .pick_batch(batch, &lang, pick.content_lang, pick.force)
+                .await;
+        }
+
+        let id = pick
+            .id
+            .ok_or_else(|| LeetUpError::Any(anyhow!("Problem ID or slug is required")))?;
+        let item = self.find_problem(&id).await?;
+        let problem = self.new_problem(&item, &lang);
 
         let problem_id = problem.id;
         let slug = problem.slug.to_owned();
+        let detail = check_paid_only(
+            &slug,
+            item.paid_only,
+            self.get_question_detail(&slug, pick.content_lang).await,
+        )?;
+
+        let path = self.generate_problem_stub(
+            &lang,
+            &problem,
+            problem_id,
+            slug.to_owned(),
+            &detail,
+            pick.force,
+        )?;
+        if let Some(ref path) = path {
+            let last_pick = LastPick {
+                id: problem_id,
+                slug,
+                path: path.to_owned(),
+            };
+            if let Err(e) = last_pick.save() {
+                warn!("Failed to remember the picked problem: {}", e);
+            }
+        }
+        // The file is closed once generated, so the editor sees all of it.
+        if let (Some(path), true) = (path, pick.open_editor || self.config.open_editor) {
+            editor::open(&path)?;
+        }
+
+        Ok(())
+    }
+
+    async fn problem_stats(&mut self, stats: cmd::Stats) -> Result<()> {
+        let res: ListResponse = serde_json::from_value(self.fetch_all_problems().await?)?;
+        let weeks = stats.weeks;
+        let buckets = AcceptanceBuckets::new(&res.stat_status_pairs, stats.bucket_width);
+        let stats = ProblemStats::from(&res);
+        if !stats.logged_in {
+            println!(
+                "{}",
+                Color::Yellow("Not logged in, solved counts are anonymous zeros").make()
+            );
+        }
+        print!("{}", stats);
+        println!();
+        print!("{}", stats.score(&self.config.score_weights));
+        println!();
+        println!("Acceptance");
+        print!("{}", format_histogram(&buckets.ranges(), 40));
+        if weeks > 0 {
+            println!();
+            print!("{}", History::open()?.weekly_solved(weeks)?);
+        }
+
+        Ok(())
+    }
+
+    async fn open_problem(&mut self, open: cmd::Open) -> Result<()> {
+        let item = self.find_problem(&open.id).await?;
+        let url = format!(
+            "{}{}/",
+            self.config.urls.problems, item.stat.question_title_slug
+        );
+        if item.paid_only {
+            println!(
+                "{}",
+                Color::Yellow("Premium problem, it requires a subscription and login").make()
+            );
+        }
+
+        if open.print {
+            println!("{}", url);
+        } else {
+            println!("Opening {}", url);
+            browser::open(&url)?;
+        }
+
+        Ok(())
+    }
+
+    async fn show_problem(&mut self, show: cmd::Show) -> Result<()> {
+        let item = self.find_problem(&show.id).await?;
+        if show.hints || show.hint.is_some() {
+            let hints = self
+                .get_question_hints(&item.stat.question_title_slug)
+                .await?;
+            return print_hints(&hints, show.hint, &item.stat.question_title_slug);
+        }
+        let detail = self
+            .get_question_detail(&item.stat.question_title_slug, show.content_lang)
+            .await?;
+
+        println!(
+            "[{}] {} {}\n{}{}/\n",
+            item.stat.frontend_question_id,
+            item.stat.question_title,
+            item.difficulty,
+            self.config.urls.problems,
+            item.stat.question_title_slug
+        );
+        match detail.content.as_deref() {
+            Some(content) => println!("{}", html::to_text(content, 80)),
+            None => println!(
+                "{}",
+                Color::Yellow("No description available, the problem may be locked").make()
+            ),
+        }
+
+        Ok(())
+    }
+
+    async fn problem_info(&mut self, info: cmd::Info) -> Result<()> {
+        let item = self.find_problem(&info.id).await?;
+        let slug = &item.stat.question_title_slug;
+        // Works from the cached list alone when offline.
+        let detail = if self.config.offline {
+            QuestionDetail::default()
+        } else {
+            let query = r#"
+                query getQuestionInfo($titleSlug: String!) {
+                   question(titleSlug: $titleSlug) {
+                     likes
+                     dislikes
+                     topicTags { name }
+                   }
+                }
+            "#;
+            self.remote_client
+                .graphql::<QuestionData>("getQuestionInfo", query, json!({ "titleSlug": slug }))
+                .await?
+                .question
+                .unwrap_or_default()
+        };
+        let url = format!("{}{}/", self.config.urls.problems, slug);
+        println!("{}", problem_info(&item, &detail, &url));
+
+        Ok(())
+    }
+
+    async fn problem_note(&mut self, note: cmd::Note) -> Result<()> {
+        let item = self.find_problem(&note.id).await?;
+        let id = item.question_id();
+        let notes = Notes::open()?;
+        if note.show {
+            match notes.read(id)? {
+                Some(text) => print!("{}", text),
+                None => println!(
+                    "{}",
+                    Color::Yellow(&format!(
+                        "No note about problem {} yet, write one with `leetup note {}`",
+                        id, id
+                    ))
+                    .make()
+                ),
+            }
+            return Ok(());
+        }
+
+        let path = notes.create(id, item.question_title())?;
+        editor::open(&path)
+    }
+
+    async fn tags_report(&mut self, report: cmd::TagsReport) -> Result<()> {
+        let slugs: Vec<String> = self
+            .fetch_problems()
+            .await?
+            .into_iter()
+            .filter(|item| item.status.is_some() != report.unsolved)
+            .map(|item| item.stat.question_title_slug)
+            .collect();
+        let kind = if report.unsolved {
+            "unsolved"
+        } else {
+            "solved"
+        };
+        if slugs.is_empty() {
+            println!("{}", Color::Yellow(&format!("No {} problems", kind)).make());
+            return Ok(());
+        }
+
+        let tags: Vec<Vec<String>> = self.problem_tags(slugs).await?.into_values().collect();
+
+        println!("Tags of {} {} problems", tags.len(), kind);
+        print!("{}", format_histogram(&tag_histogram(&tags), 40));
+
+        Ok(())
+    }
+
+    async fn export_problems(&mut self, export: cmd::Export) -> Result<()> {
+        if export.schema {
+            println!("{}", serde_json::to_string_pretty(&export_schema())?);
+            return Ok(());
+        }
+
+        let problems = self.fetch_problems().await?;
+        let mut tags = if export.tags {
+            let slugs = problems
+                .iter()
+                .map(|item| item.stat.question_title_slug.to_owned())
+                .collect();
+            Some(self.problem_tags(slugs).await?)
+        } else {
+            None
+        };
+        let exported = problems.iter().map(|item| ExportedProblem {
+            summary: ProblemSummary::from(item as &(dyn ProblemInfo + Send)),
+            tags: tags.as_mut().map(|tags| {
+                tags.remove(&item.stat.question_title_slug)
+                    .unwrap_or_default()
+            }),
+        });
+
+        match export.output {
+            Some(ref path) => {
+                let file = File::create(shellexpand::tilde(path).as_ref())?;
+                write_export(BufWriter::new(file), exported, export.format, export.pretty)?;
+                println!("Exported {} problems to {}", problems.len(), path);
+            }
+            None => write_export(io::stdout().lock(), exported, export.format, export.pretty)?,
+        }
+
+        Ok(())
+    }
+
+    async fn similar_problems(&mut self, similar: cmd::Similar) -> Result<()> {
+        let item = self.find_problem(&similar.id).await?;
+        let slug = item.stat.question_title_slug;
         let query = r#"
-            query getQuestionDetail($titleSlug: String!) {
+            query getSimilarQuestions($titleSlug: String!) {
                question(titleSlug: $titleSlug) {
+                 similarQuestions
+               }
+            }
+        "#;
+        let similar_questions = self
+            .remote_client
+            .graphql::<QuestionData>("getSimilarQuestions", query, json!({ "titleSlug": slug }))
+            .await?
+            .question
+            .ok_or_else(|| LeetUpError::ProblemNotFound(slug.to_string()))?
+            .similar_questions;
+        let similar_questions: Vec<SimilarQuestion> = match similar_questions.as_deref() {
+            Some(json) if !json.trim().is_empty() => serde_json::from_str(json)?,
+            _ => vec![],
+        };
+        if similar_questions.is_empty() {
+            println!(
+                "{}",
+                Color::Yellow(&format!("Problem `{}` has no similar problems", slug)).make()
+            );
+            return Ok(());
+        }
+
+        // Rows come from the cached list, which knows the solved status.
+        let mut problems: HashMap<String, StatStatusPair> = self
+            .fetch_problems()
+            .await?
+            .into_iter()
+            .map(|item| (item.stat.question_title_slug.to_owned(), item))
+            .collect();
+        let probs: ProblemInfoSeq = similar_questions
+            .iter()
+            .filter_map(|question| problems.remove(&question.title_slug))
+            .map(|item| Box::new(item) as Box<dyn ProblemInfo + Send>)
+            .collect();
+        Leetcode::pretty_list(&probs);
+
+        Ok(())
+    }
+
+    async fn star_problem(&mut self, star: cmd::Star, starred: bool) -> Result<()> {
+        self.require_session()?;
+        let id = match (star.id, star.query) {
+            (_, Some(query)) => return self.star_matching(&query, star.yes, starred).await,
+            (Some(id), None) => id,
+            (None, None) => return Err(LeetUpError::UnexpectedCommand),
+        };
+        let item = self.find_problem(&id).await?;
+        let slug = &item.stat.question_title_slug;
+        let state = if starred { "starred" } else { "not starred" };
+        if item.is_favor == starred {
+            println!(
+                "{}",
+                Color::Yellow(&format!("Problem `{}` is already {}", slug, state)).make()
+            );
+            return Ok(());
+        }
+
+        let hash = self.favorite_hash().await?;
+        self.set_favorite(&hash, &item, starred).await?;
+        self.clear_problems_cache();
+        println!(
+            "{}",
+            Color::Green(&format!("Problem `{}` is now {}", slug, state)).make()
+        );
+
+        Ok(())
+    }
+
+    async fn problem_test(&mut self, test: cmd::Test) -> Result<()> {
+        if test.dry_run {
+            let problem = self.extract_problem(test.filename)?;
+            // The sample test case is only known after asking LeetCode for it.
+            let test_data = match test.test_data {
+                Some(data) => self.get_test_data(Some(data), &problem).await?,
+                None => "sample test case of the problem".to_string(),
+            };
+            println!("Testcase: {}", test_data);
+            self.print_dry_run(&self.config.urls.test, &problem);
+            return Ok(());
+        }
+
+        self.require_session()?;
+        let poll = self
+            .config
+            .poll_settings(test.poll_interval, test.poll_timeout)?;
+        let solution = PathBuf::from(&test.filename);
+        let problem = self.extract_problem(test.filename)?;
+
+        let test_data = if test.edit {
+            self.edit_test_data(&problem).await?
+        } else {
+            self.get_test_data(test.test_data.clone(), &problem).await?
+        };
+        if test.edit || test.test_data.is_some() {
+            self.cache.set(
+                self.cache_key(CacheKey::TestCase(&problem.slug)),
+                test_data.to_owned(),
+            )?;
+        }
+        debug!("Test data: {:?}", test_data);
+        let typed_code = parse_code(problem.typed_code.as_ref().expect("Expected typed_code"));
+        let body = json!({
+                "lang":        problem.lang.to_owned(),
+                "question_id": problem.id,
+                "typed_code":  typed_code,
+                "data_input":  test_data,
+                "judge_type":  "large"
+        });
+        let url = &self.config()?.urls.test;
+        debug!("problem_test url: {}, {:?}", url, body);
+        let json = self.run_code(url, &problem, body).await?;
+        debug!("problem_test response: {:?}", json);
+
+        let interpret_id = json["interpret_id"]
+            .as_str()
+            .ok_or_else(|| LeetUpError::Any(anyhow!("Unable to replace `interpret_id`")))?;
+        let url = self.config.urls.verify.replace("$id", interpret_id);
+        let result: SubmissionResponse =
+            serde_json::from_value(self.verify_run_code(&url, interpret_id, poll).await?)?;
+        let execution_result = TestExecutionResult::new(test_data.into(), result);
+        execution_result.print();
+        let time = format!("{} UTC", format_timestamp(unix_now()));
+        if let Some(log) = execution_result.failure_log(&time) {
+            let path = self.append_test_log(&solution, &problem, &log)?;
+            println!("Failed cases logged to {}", path.display());
+        }
+
+        Ok(())
+    }
+
+    async fn problem_submit(&mut self, submit: cmd::Submit) -> Result<()> {
+        if !submit.dry_run {
+            self.require_session()?;
+        }
+        let poll = self
+            .config
+            .poll_settings(submit.poll_interval, submit.poll_timeout)?;
+        // Without a file, submit the one picked for --id or the last pick.
+        let filename = match (submit.filename, &submit.id) {
+            _ if submit.stdin => None,
+            (Some(filename), _) => Some(filename),
+            (None, Some(id)) => {
+                let item = self.find_problem(id).await?;
+                let path = self.picked_solution_path(&item, submit.lang.as_ref())?;
+                Some(path.display().to_string())
+            }
+            (None, None) => {
+                let last_pick = LastPick::load()?.ok_or_else(|| {
+                    LeetUpError::Any(anyhow!(
+                        "No problem picked yet, pass the solution file or --id"
+                    ))
+                })?;
+                println!("Submitting {}", last_pick.path.display());
+                Some(last_pick.path.display().to_string())
+            }
+        };
+        let problem = match (filename, submit.id, submit.lang) {
+            (_, Some(id), Some(lang)) if submit.stdin => self.stdin_problem(&id, &lang).await?,
+            (Some(filename), _, Some(lang)) => {
+                check_lang_override(&filename, &lang, submit.force)?;
+                let mut problem = self.extract_problem(filename)?;
+                problem.lang = lang.slug().to_string();
+                problem
+            }
+            (Some(filename), ..) => self.extract_problem(filename)?,
+            _ => return Err(LeetUpError::UnexpectedCommand),
+        };
+        if submit.dry_run {
+            self.print_dry_run(&self.config.urls.submit, &problem);
+            return Ok(());
+        }
+        let body = json!({
+            "lang":        problem.lang.to_owned(),
+            "question_id": problem.id,
+            "test_mode":   false,
+            "typed_code":  parse_code(problem.typed_code.as_ref().expect("Expected typed_code")),
+            "judge_type": "large",
+        });
+        let url = &self.config()?.urls.submit;
+        let response = self.run_code(url, &problem, body).await?;
+        let submission_id = match &response["submission_id"] {
+            Value::Number(id) => id.to_string(),
+            Value::String(id) => id.to_owned(),
+            _ => {
+                return Err(LeetUpError::Any(anyhow!(
+                    "Submission was not accepted by the judge: {}",
+                    response
+                )))
+            }
+        };
+        let url = self.config.urls.verify.replace("$id", &submission_id);
+        let result: SubmissionResponse =
+            serde_json::from_value(self.verify_run_code(&url, &submission_id, poll).await?)?;
+        let entry = HistoryEntry {
+            timestamp: unix_now(),
+            id: problem.id,
+            slug: problem.slug.to_owned(),
+            lang: problem.lang.to_owned(),
+            verdict: result.status_msg.to_owned(),
+            runtime: result.status_runtime.to_owned(),
+            memory: result.status_memory.to_owned(),
+        };
+        if let Err(e) = History::open().and_then(|history| history.append(&entry)) {
+            warn!("Failed to record submission: {}", e);
+        }
+        let execution_result = SubmitExecutionResult::new(result);
+        execution_result.print();
+        Ok(())
+    }
+
+    async fn submission_result(&mut self, result: cmd::SubmissionResult) -> Result<()> {
+        self.require_session()?;
+        let submission_id = result.submission_id.to_string();
+        let url = self.config.urls.verify.replace("$id", &submission_id);
+        let response: Value = self.remote_client.get_json(&url, self.session()).await?;
+        match response["state"].as_str() {
+            Some("SUCCESS") => {
+                let result: SubmissionResponse = serde_json::from_value(response)?;
+                SubmitExecutionResult::new(result).print();
+            }
+            Some("PENDING") | Some("STARTED") => println!(
+                "Submission {} is still being judged, try again in a moment",
+                submission_id
+            ),
+            _ => {
+                return Err(LeetUpError::Any(anyhow!(
+                    "No result for submission {}: {}",
+                    submission_id,
+                    response
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    async fn diff_solution(&mut self, diff: cmd::Diff) -> Result<()> {
+        self.require_session()?;
+        let item = self.find_problem(&diff.id).await?;
+        let path = match diff.filename {
+            Some(filename) => filename,
+            None => self
+                .picked_solution_path(&item, diff.lang.as_ref())?
+                .display()
+                .to_string(),
+        };
+        if !Path::new(&path).is_file() {
+            return Err(LeetUpError::Any(anyhow!(
+                "No solution at `{}`, pick the problem first or pass the file to compare",
+                path
+            )));
+        }
+        let problem = self.extract_problem(path.clone())?;
+        if problem.slug != item.stat.question_title_slug {
+            return Err(LeetUpError::Any(anyhow!(
+                "`{}` is a solution to `{}`, not `{}`",
+                path,
+                problem.slug,
+                item.stat.question_title_slug
+            )));
+        }
+
+        let accepted = self
+            .last_accepted(&problem.slug, &problem.lang)
+            .await?
+            .ok_or_else(|| {
+                LeetUpError::Any(anyhow!(
+                    "No accepted {} submission of `{}` yet, nothing to compare with",
+                    problem.lang,
+                    problem.slug
+                ))
+            })?;
+        let local = problem
+            .typed_code
+            .as_deref()
+            .and_then(parse_code)
+            .unwrap_or_default();
+        let out = diff::unified_diff(
+            &accepted.code,
+            &local,
+            &format!("submission {}", accepted.id),
+            &path,
+        );
+        if out.is_empty() {
+            println!("No changes since submission {}", accepted.id);
+        } else {
+            print!("{}", out);
+        }
+
+        Ok(())
+    }
+
+    async fn process_auth(&mut self, user: User) -> Result<()> {
+        // cookie login
+        if let Some(cookie) = user.cookie {
+            let session = match cookie {
+                Some(path) => Session::from_cookie_file(shellexpand::tilde(&path).as_ref())?,
+                None => auth::cookie_login(self).await?,
+            };
+            self.validate_session(&session).await?;
+            self.cache_session(session)?;
+            println!("{}", Color::Green("User logged in!").make());
+        }
+
+        if user.logout.is_some() {
+            self.logout()?;
+            println!("User logged out!");
+        }
+
+        Ok(())
+    }
+
+    async fn whoami(&mut self) -> Result<()> {
+        let host = url::Url::parse(&self.config.urls.base)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| self.config.urls.base.to_owned());
+        let Some(session) = self.session else {
+            println!("Not logged in to {}", host);
+            return Err(LeetUpError::NotLoggedIn);
+        };
+
+        let claims = session.claims();
+        // The problem list, cached per session, names the user it was
+        // fetched for. It is empty when LeetCode didn't accept the session.
+        let user_name = match self.fetch_all_problems().await {
+            Ok(res) => serde_json::from_value::<ListResponse>(res)?.user_name,
+            Err(LeetUpError::NoCachedProblems) => claims
+                .as_ref()
+                .and_then(|claims| claims.username.to_owned())
+                .unwrap_or_else(|| "unknown, nothing cached offline".to_string()),
+            Err(e) => return Err(e),
+        };
+        if user_name.is_empty() {
+            return Err(LeetUpError::SessionExpired);
+        }
+        println!("User     {}", user_name);
+        println!("Site     {}", host);
+        match claims.and_then(|claims| claims.expires) {
+            Some(expires) if expires <= unix_now() => {
+                println!("Expires  {} UTC (expired)", format_timestamp(expires))
+            }
+            Some(expires) => println!("Expires  {} UTC", format_timestamp(expires)),
+            None => println!("Expires  unknown"),
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Leetcode<'a> {
+    pub fn new(session: Option<&'a Session>, config: &'a Config, cache: KvStore) -> Result<Self> {
+        let cache_namespace = cache_namespace(&config.urls.base, session);
+
+        Ok(Leetcode {
+            session,
+            config,
+            cache,
+            snapshot: Snapshot::open(&cache_namespace)?,
+            cache_namespace,
+            remote_client: RemoteClient::new(config, session),
+        })
+    }
+
+    /// Cache key in the namespace of the current site and user.
+    fn cache_key(&self, key: CacheKey) -> String {
+        key.namespaced(&self.cache_namespace)
+    }
+
+    /// Session of the logged in user, required by authenticated commands.
+    fn require_session(&self) -> Result<&'a Session> {
+        self.session.ok_or(LeetUpError::NotLoggedIn)
+    }
+
+    /// Make sure the session is accepted by LeetCode before persisting it.
+    async fn validate_session(&self, session: &Session) -> Result<()> {
+        let response: Value = self
+            .remote_client
+            .get_json(&self.config.urls.problems("all"), Some(session))
+            .await?;
+
+        match response["user_name"].as_str() {
+            Some(user_name) if !user_name.is_empty() => Ok(()),
+            _ => Err(LeetUpError::SessionExpired),
+        }
+    }
+
+    fn cache_session(&mut self, session: Session) -> Result<()> {
+        let session_str = serde_json::to_string(&session)?;
+        self.cache.set(CacheKey::Session.into(), session_str)?;
+        // remove key `problems`, rebuild problems cache.
+        //
+        self.clear_problems_cache();
+        Ok(())
+    }
+
+    /// Whether the fetch time stored at `fetched_at_key` is within `cache_ttl`.
+    fn is_cache_fresh(&mut self, fetched_at_key: CacheKey) -> Result<bool> {
+        let fetched_at = self
+            .cache
+            .get(self.cache_key(fetched_at_key))?
+            .and_then(|val| val.parse::<u64>().ok());
+
+        Ok(fetched_at
+            .map(|fetched_at| unix_now().saturating_sub(fetched_at) < self.config.cache_ttl())
+            .unwrap_or(false))
+    }
+
+    fn clear_problems_cache(&mut self) {
+        // NOTE: cache.remove throws "Key not found" error
+        // so ignore that error if it is thrown.
+        let _ = self.snapshot.remove();
+        let _ = self
+            .cache
+            .remove(self.cache_key(CacheKey::ProblemsFetchedAt));
+        let _ = self.cache.remove(self.cache_key(CacheKey::ProblemsETag));
+        let _ = self.cache.remove(self.cache_key(CacheKey::ProblemsHash));
+    }
+
+    pub async fn fetch_problems(&mut self) -> Result<Vec<StatStatusPair>> {
+        let problems = self.fetch_all_problems().await?;
+        let problems: Vec<StatStatusPair> =
+            serde_json::from_value(problems["stat_status_pairs"].clone())?;
+
+        Ok(problems)
+    }
+
+    fn new_problem(&self, item: &StatStatusPair, lang: &LangInfo) -> Problem {
+        Problem {
+            id: item.stat.frontend_question_id,
+            link: format!(
+                "{}{}/",
+                self.config.urls.problems, item.stat.question_title_slug
+            ),
+            slug: item.stat.question_title_slug.to_string(),
+            lang: lang.name.to_owned(),
+            typed_code: None,
+            difficulty: DifficultyType::from(&item.difficulty),
+        }
+    }
+
+    /// Pick all problems listed in `path`, fetching up to `BATCH_CONCURRENCY`
+    /// of them at once. Failures are reported once every problem was tried.
+    async fn pick_batch(
+        &mut self,
+        path: &str,
+        lang: &LangInfo,
+        content_lang: cmd::ContentLang,
+        force: bool,
+    ) -> Result<()> {
+        let content = fs::read_to_string(shellexpand::tilde(path).as_ref())?;
+        let ids: Vec<&str> = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+
+        let problems = self.fetch_problems().await?;
+        let mut failures: Vec<(String, LeetUpError)> = vec![];
+        let mut found = vec![];
+        for id in &ids {
+            match problems.iter().find(|item| is_problem(item, id)) {
+                Some(item) => found.push((self.new_problem(item, lang), item.paid_only)),
+                None => {
+                    failures.push((id.to_string(), LeetUpError::ProblemNotFound(id.to_string())))
+                }
+            }
+        }
+
+        let bar = progress::bar(found.len(), "Picking");
+        let this = &*self;
+        let details: Vec<(Problem, Result<QuestionDetail>)> = stream::iter(found)
+            .map(|(problem, paid_only)| async move {
+                let detail = this.get_question_detail(&problem.slug, content_lang).await;
+                let detail = check_paid_only(&problem.slug, paid_only, detail);
+                (problem, detail)
+            })
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .inspect(|_| bar.inc(1))
+            .collect()
+            .await;
+        bar.finish_and_clear();
+
+        for (problem, detail) in details {
+            let slug = problem.slug.to_owned();
+            let picked = detail.and_then(|detail| {
+                self.generate_problem_stub(
+                    lang,
+                    &problem,
+                    problem.id,
+                    slug.to_owned(),
+                    &detail,
+                    force,
+                )
+            });
+            match picked {
+                Ok(_) => println!("{} {}", Color::Green("Picked").make(), slug),
+                Err(e) => failures.push((slug, e)),
+            }
+        }
+
+        if failures.is_empty() {
+            return Ok(());
+        }
+        for (id, e) in &failures {
+            println!("{} {}: {}", Color::Red("Failed").make(), id, e);
+        }
+        Err(LeetUpError::Any(anyhow!(
+            "{} of {} problems could not be picked",
+            failures.len(),
+            ids.len()
+        )))
+    }
+
+    /// Find a problem by its frontend id or title slug, falling back to a
+    /// keyword search and asking which one to use when several match.
+    async fn find_problem(&mut self, id: &str) -> Result<StatStatusPair> {
+        let problems = self.fetch_problems().await?;
+        let exact = problems.iter().position(|item| is_problem(item, id));
+        if let Some(index) = exact {
+            return Ok(problems.into_iter().nth(index).unwrap());
+        }
+
+        let mut candidates: Vec<(usize, StatStatusPair)> = problems
+            .into_iter()
+            .filter_map(|item| keyword_rank(id, &item).map(|rank| (rank, item)))
+            .collect();
+        candidates.sort_by_key(|(rank, item)| (*rank, item.stat.frontend_question_id));
+        let mut candidates: Vec<StatStatusPair> =
+            candidates.into_iter().map(|(_, item)| item).collect();
+
+        match candidates.len() {
+            0 => Err(LeetUpError::ProblemNotFound(id.to_string())),
+            1 => Ok(candidates.remove(0)),
+            _ if !stdin().is_terminal() => Err(ambiguous_problem(id, &candidates)),
+            _ => {
+                let index = Leetcode::choose_problem(&candidates)?;
+                Ok(candidates.remove(index))
+            }
+        }
+    }
+
+    /// Print a numbered list of candidates and read the chosen one from stdin.
+    fn choose_problem(candidates: &[StatStatusPair]) -> Result<usize> {
+        println!("{}", Color::Yellow("Several problems match:").make());
+        // Leave room for the numbering.
+        let width = title_width().saturating_sub(5);
+        for (i, item) in candidates.iter().enumerate() {
+            println!("{:>3}) {}", i + 1, problem_row(item, width));
+        }
+        print!("Pick a number [1-{}]: ", candidates.len());
+        io::stdout().flush()?;
+
+        let mut choice = String::new();
+        stdin().read_line(&mut choice)?;
+        choice
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|choice| (1..=candidates.len()).contains(choice))
+            .map(|choice| choice - 1)
+            .ok_or_else(|| LeetUpError::Any(anyhow!("Invalid choice `{}`", choice.trim())))
+    }
+
+    /// Fetch the problem description, code snippets and test case.
+    ///
+    /// With `ContentLang::Zh` the translated description replaces the English
+    /// one if LeetCode has it, otherwise the English one is kept with a note.
+    async fn get_question_detail(
+        &self,
+        slug: &str,
+        content_lang: cmd::ContentLang,
+    ) -> Result<QuestionDetail> {
+        // leetcode.com doesn't know `translatedContent`, only ask for it when needed.
+        let translated_content = match content_lang {
+            cmd::ContentLang::En => "",
+            cmd::ContentLang::Zh => "translatedContent",
+        };
+        let query = format!(
+            r#"
+            query getQuestionDetail($titleSlug: String!) {{
+               question(titleSlug: $titleSlug) {{
                  content
-                 stats
-                 likes
-                 dislikes
+                 {}
                  codeDefinition
                  sampleTestCase
                  enableRunCode
                  metaData
-                 translatedContent
+               }}
+            }}
+        "#,
+            translated_content
+        );
+
+        let mut detail = self
+            .remote_client
+            .graphql::<QuestionData>(
+                "getQuestionDetail",
+                &query,
+                json!({
+                    "titleSlug": slug,
+                }),
+            )
+            .await?
+            .question
+            .ok_or_else(|| LeetUpError::ProblemNotFound(slug.to_string()))?;
+        if content_lang == cmd::ContentLang::Zh && !detail.use_translation() {
+            println!(
+                "{}",
+                Color::Yellow(&format!(
+                    "No Chinese description for `{}`, showing the English one",
+                    slug
+                ))
+                .make()
+            );
+        }
+
+        Ok(detail)
+    }
+
+    /// Names of the topic tags of a problem.
+    async fn get_question_tags(&self, slug: &str) -> Result<Vec<String>> {
+        let query = r#"
+            query getQuestionTags($titleSlug: String!) {
+               question(titleSlug: $titleSlug) {
+                 topicTags { name }
                }
             }
         "#;
-        let body: Value = json!({
-            "query": query,
-            "variables": json!({
-                "titleSlug": slug.to_owned(),
-            }),
-            "operationName": "getQuestionDetail"
-        });
 
-        let response = self
-            .remote_client
-            .post(&urls.graphql, &body, || None)
-            .await?;
-        debug!("Response: {}", response);
+        Ok(self
+            .remote_client
+            .graphql::<QuestionData>("getQuestionTags", query, json!({ "titleSlug": slug }))
+            .await?
+            .question
+            .ok_or_else(|| LeetUpError::ProblemNotFound(slug.to_string()))?
+            .topic_tags
+            .unwrap_or_default()
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect())
+    }
+
+    async fn get_question_hints(&self, slug: &str) -> Result<Vec<String>> {
+        let query = r#"
+            query getQuestionHints($titleSlug: String!) {
+               question(titleSlug: $titleSlug) {
+                 hints
+               }
+            }
+        "#;
+
+        Ok(self
+            .remote_client
+            .graphql::<QuestionData>("getQuestionHints", query, json!({ "titleSlug": slug }))
+            .await?
+            .question
+            .ok_or_else(|| LeetUpError::ProblemNotFound(slug.to_string()))?
+            .hints
+            .unwrap_or_default())
+    }
+
+    /// Today's daily challenge.
+    async fn fetch_daily(&self) -> Result<DailyChallenge> {
+        let query = r#"
+            query questionOfToday {
+                activeDailyCodingChallengeQuestion {
+                    date
+                    link
+                    question {
+                        questionFrontendId
+                        title
+                        titleSlug
+                        difficulty
+                    }
+                }
+            }
+        "#;
+        Ok(self
+            .remote_client
+            .graphql::<DailyChallengeData>("questionOfToday", query, json!({}))
+            .await?
+            .daily_challenge)
+    }
+
+    fn print_daily(&self, daily_challenge: &DailyChallenge) {
+        let question = &daily_challenge.question;
+        // The daily challenge resets at midnight UTC, so show the date
+        // LeetCode returns instead of the local date.
+        println!(
+            "{} (UTC)\n[{:^4}] {} {}\n{}{}",
+            Color::Cyan(&daily_challenge.date).make(),
+            question.question_frontend_id,
+            question.title,
+            question.difficulty,
+            self.config.urls.base,
+            daily_challenge.link
+        );
+    }
+
+    /// Check the daily challenge every interval, announcing each new one,
+    /// until Ctrl-C. Failed checks are reported and retried next time.
+    async fn watch_daily(&self, daily: &cmd::Daily) -> Result<()> {
+        let interval = self.config.daily_watch_interval(daily.interval)?;
+        let notify = daily.notify || self.config.daily_notify;
+        let mut last_date = None;
+        loop {
+            match self.fetch_daily().await {
+                Ok(challenge) if last_date.as_ref() != Some(&challenge.date) => {
+                    if last_date.is_some() {
+                        println!("\n{}", Color::Green("New daily challenge!").make());
+                        if notify {
+                            let body = format!(
+                                "{}. {}",
+                                challenge.question.question_frontend_id, challenge.question.title
+                            );
+                            if let Err(e) = notify::send("New daily challenge", &body) {
+                                eprintln!("Warning: {}", e);
+                            }
+                        }
+                    }
+                    self.print_daily(&challenge);
+                    last_date = Some(challenge.date);
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Warning: failed to check the daily challenge: {}", e),
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = tokio::signal::ctrl_c() => {
+                    println!("\nStopped watching the daily challenge");
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Topic tags of each problem in `slugs`, by slug.
+    async fn problem_tags(&mut self, slugs: Vec<String>) -> Result<HashMap<String, Vec<String>>> {
+        // Tags rarely change, so each problem's tags are only fetched once.
+        let mut tags = HashMap::new();
+        let mut missing = vec![];
+        for slug in slugs {
+            match self.cache.get(self.cache_key(CacheKey::Tags(&slug)))? {
+                Some(val) => {
+                    tags.insert(slug, serde_json::from_str::<Vec<String>>(&val)?);
+                }
+                None => missing.push(slug),
+            }
+        }
+        info!("Tags cache hit for {} problems", tags.len());
+        let bar = progress::bar(missing.len(), "Fetching tags");
+        let fetched: Vec<(String, Result<Vec<String>>)> = stream::iter(missing)
+            .map(|slug| async {
+                let tags = self.get_question_tags(&slug).await;
+                (slug, tags)
+            })
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .inspect(|_| bar.inc(1))
+            .collect()
+            .await;
+        bar.finish_and_clear();
+        // Cache what was fetched even if some requests failed, so running
+        // the command again only fetches the rest.
+        let mut failure = None;
+        for (slug, problem_tags) in fetched {
+            match problem_tags {
+                Ok(problem_tags) => {
+                    self.cache.set(
+                        self.cache_key(CacheKey::Tags(&slug)),
+                        serde_json::to_string(&problem_tags)?,
+                    )?;
+                    tags.insert(slug, problem_tags);
+                }
+                Err(e) => failure = Some(e),
+            }
+        }
+        if let Some(e) = failure {
+            return Err(e);
+        }
+
+        Ok(tags)
+    }
+
+    /// Problems selected by `list`'s filters, in its order.
+    async fn query_problems(&mut self, list: &List) -> Result<ProblemInfoSeq> {
+        self.require_session()?;
+        let profiled;
+        let list = match list.profile {
+            Some(ref name) => {
+                profiled = list.with_profile(self.config.profile(name)?);
+                &profiled
+            }
+            None => list,
+        };
+
+        let query = list.query();
+        if let (Some(query), false) = (&query, list.force) {
+            Query::check_conflicts(&Query::from_str(query)?)?;
+        }
+
+        if list.refresh {
+            if self.config.offline {
+                return Err(LeetUpError::Offline("Refreshing problems".into()));
+            }
+            self.clear_problems_cache();
+            if let Some(ref company) = list.company {
+                let _ = self
+                    .cache
+                    .remove(self.cache_key(CacheKey::CompanyFetchedAt(company)));
+            }
+        }
+
+        let problems_res = self.fetch_all_problems().await?;
+        // LeetCode answers a stale session like an anonymous request, without
+        // any status, so `--solved` would match nothing and `--unsolved` all.
+        let anonymous = problems_res["user_name"]
+            .as_str()
+            .is_none_or(|user_name| user_name.is_empty());
+        if anonymous && list.filters_status()? {
+            return Err(LeetUpError::SessionExpired);
+        }
+        let mut probs: ProblemInfoSeq = vec![];
+
+        if !list.tag.is_empty() {
+            let mut problems: Vec<TopicTagQuestion> = vec![];
+            for (i, tag) in list.tag.iter().enumerate() {
+                let tag_questions = self.get_topic_tag_questions(tag).await?;
+                if i == 0 {
+                    problems = tag_questions;
+                } else {
+                    let ids: HashSet<String> = tag_questions
+                        .into_iter()
+                        .map(|q| q.question_frontend_id)
+                        .collect();
+                    problems.retain(|q| ids.contains(&q.question_frontend_id));
+                }
+            }
+            for prob in problems {
+                probs.push(Box::new(prob));
+            }
+        } else if let Some(categories) = list.categories() {
+            if self.config.offline {
+                return Err(LeetUpError::Offline("Listing by category".into()));
+            }
+            for prob in self.fetch_category_problems(&categories).await? {
+                probs.push(Box::new(prob));
+            }
+        } else {
+            let problems: Vec<StatStatusPair> =
+                serde_json::from_value(problems_res["stat_status_pairs"].clone())?;
+
+            for prob in problems {
+                probs.push(Box::new(prob));
+            }
+        }
 
-        self.generate_problem_stub(&lang, &problem, problem_id, slug, &response)?;
+        if let Some(ref company) = list.company {
+            let ids: HashSet<usize> = self
+                .get_company_questions(company)
+                .await?
+                .iter()
+                .map(|q| q.question_id())
+                .collect();
+            probs.retain(|prob| ids.contains(&prob.question_id()));
+        }
 
-        Ok(())
-    }
+        if list.new {
+            // Tag listings don't say which problems are new, the full list does.
+            let problems: Vec<StatStatusPair> =
+                serde_json::from_value(problems_res["stat_status_pairs"].clone())?;
+            let ids: HashSet<usize> = problems
+                .iter()
+                .filter(|item| item.is_new() == Some(true))
+                .map(|item| item.question_id())
+                .collect();
+            probs.retain(|prob| ids.contains(&prob.question_id()));
+        }
 
-    async fn problem_test(&self, test: cmd::Test) -> Result<()> {
-        let problem = service::extract_problem(test.filename)?;
+        if list.has_note {
+            let ids = Notes::open()?.ids()?;
+            probs.retain(|prob| ids.contains(&prob.question_id()));
+        }
 
-        let test_data = self.get_test_data(test.test_data);
-        debug!("Test data: {:?}", test_data);
-        let typed_code = parse_code(problem.typed_code.as_ref().expect("Expected typed_code"));
-        let body = json!({
-                "lang":        problem.lang.to_owned(),
-                "question_id": problem.id,
-                "typed_code":  typed_code,
-                "data_input":  test_data,
-                "judge_type":  "large"
+        let orders = list.order.as_deref().map(OrderBy::from_str).transpose()?;
+        probs.sort_by(|a, b| {
+            let ordering = match orders {
+                Some(ref orders) => with_ordering(orders.as_slice(), a.as_ref(), b.as_ref()),
+                None => a.cmp(b),
+            };
+            if list.reverse {
+                ordering.reverse()
+            } else {
+                ordering
+            }
         });
-        let url = &self.config()?.urls.test;
-        debug!("problem_test url: {}, {:?}", url, body);
-        let response = self.run_code(url, &problem, body).await;
-        debug!("problem_test response: {:?}", response);
 
-        match response {
-            Err(e) => {
-                println!("\n\n{}", Color::Red(e.to_string().as_str()).make());
-                println!(
-                    "\n{}",
-                    Color::Yellow("Note: If error status is 4XX, make sure you are logged in!")
-                        .make()
-                );
-            }
-            Ok(json) => {
-                let url = self.config.urls.verify.replace(
-                    "$id",
-                    json["interpret_id"].as_str().ok_or_else(|| {
-                        LeetUpError::Any(anyhow!("Unable to replace `interpret_id`"))
-                    })?,
-                );
-                let result: SubmissionResponse =
-                    serde_json::from_value(self.verify_run_code(&url).await?)?;
-                let execution_result = TestExecutionResult::new(test_data.into(), result);
-                execution_result.print();
-            }
+        let mut probs =
+            Leetcode::filter_problems(probs, list.keyword.as_deref(), query.as_deref())?;
+        if let Some(range) = list.range {
+            probs.retain(|prob| range.contains(prob.question_id()));
         }
 
-        Ok(())
+        Ok(probs)
     }
 
-    async fn problem_submit(&self, submit: cmd::Submit) -> Result<()> {
-        let problem = service::extract_problem(submit.filename)?;
-        let body = json!({
-            "lang":        problem.lang.to_owned(),
-            "question_id": problem.id,
-            "test_mode":   false,
-            "typed_code":  parse_code(problem.typed_code.as_ref().expect("Expected typed_code")),
-            "judge_type": "large",
-        });
-        let url = &self.config()?.urls.submit;
-        let response = self.run_code(url, &problem, body).await?;
-        let url = self
-            .config
-            .urls
-            .verify
-            .replace("$id", &response["submission_id"].to_string());
-        let result: SubmissionResponse = serde_json::from_value(self.verify_run_code(&url).await?)?;
-        let execution_result = SubmitExecutionResult::new(result);
-        execution_result.print();
-        Ok(())
+    /// Keep problems matching `keyword` and satisfying `query`.
+    fn filter_problems(
+        probs: ProblemInfoSeq,
+        keyword: Option<&str>,
+        query: Option<&str>,
+    ) -> Result<ProblemInfoSeq> {
+        let keyword = keyword.unwrap_or_default();
+        let queries = query.map(Query::from_str).transpose()?;
+        let filter_predicate = |o: &(dyn ProblemInfo + Send)| {
+            let has_keyword = matches_keyword(keyword, o);
+
+            queries
+                .as_ref()
+                .map(|queries| apply_queries(queries, o))
+                .map(|result| has_keyword && result)
+                .unwrap_or(has_keyword)
+        };
+
+        Ok(probs
+            .into_iter()
+            .filter(|o| filter_predicate(o.as_ref()))
+            .collect())
     }
 
-    async fn process_auth(&mut self, user: User) -> Result<()> {
-        // cookie login
-        if user.cookie.is_some() {
-            let session = auth::cookie_login(self).await?;
-            self.cache_session(session)?;
+    /// Problem of a solution file, linked to the selected site, which must
+    /// have its code between markers.
+    fn extract_problem(&self, filename: String) -> Result<Problem> {
+        let mut problem = service::extract_problem(&filename)?;
+        let typed_code = problem.typed_code.as_deref().unwrap_or_default();
+        if extract_marked_code(typed_code).is_none() {
+            return Err(LeetUpError::MissingCodeMarkers { path: filename });
         }
+        problem.lang = problem.lang.parse::<Lang>()?.slug().to_string();
+        problem.link = format!("{}{}/submissions/", self.config.urls.problems, problem.slug);
 
-        if user.logout.is_some() {
-            self.logout()?;
-            println!("User logged out!");
+        Ok(problem)
+    }
+
+    /// Add `item` to the favorites list `hash`, or remove it from it.
+    async fn set_favorite(&self, hash: &str, item: &StatStatusPair, starred: bool) -> Result<()> {
+        let referer = format!(
+            "{}{}/",
+            self.config.urls.problems, item.stat.question_title_slug
+        );
+        let headers = || {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::REFERER, HeaderValue::from_str(&referer).ok()?);
+            Some(headers)
+        };
+        if starred {
+            let body = json!({
+                "favorite_id_hash": hash,
+                "question_id": item.stat.question_id,
+                "add": true,
+            });
+            self.remote_client
+                .post(&self.config.urls.favorites, &body, headers)
+                .await?;
+        } else {
+            let url = self
+                .config
+                .urls
+                .favorite_delete
+                .replace("$hash", hash)
+                .replace("$id", &item.stat.question_id.to_string());
+            self.remote_client.delete(&url, headers()).await?;
         }
 
         Ok(())
     }
 
-    fn cache(&mut self) -> Result<&KvStore> {
-        Ok(&self.cache)
-    }
+    /// Star or unstar every problem matching `query`, up to
+    /// `BATCH_CONCURRENCY` at a time, after confirming unless `yes` is set.
+    async fn star_matching(&mut self, query: &str, yes: bool, starred: bool) -> Result<()> {
+        let queries = Query::from_str(query)?;
+        Query::check_conflicts(&queries)?;
+        let items: Vec<StatStatusPair> = self
+            .fetch_problems()
+            .await?
+            .into_iter()
+            .filter(|item| item.is_favor != starred && apply_queries(&queries, item))
+            .collect();
+        let (action, done) = if starred {
+            ("Star", "Starred")
+        } else {
+            ("Unstar", "Unstarred")
+        };
+        if items.is_empty() {
+            println!(
+                "{}",
+                Color::Yellow(&format!("No problem to {}", action.to_lowercase())).make()
+            );
+            return Ok(());
+        }
+        if !yes && !confirm(&format!("{} {} problems?", action, items.len()))? {
+            println!("{}", Color::Yellow("Nothing changed").make());
+            return Ok(());
+        }
 
-    fn name(&self) -> &'a str {
-        self.name
+        let total = items.len();
+        let hash = self.favorite_hash().await?;
+        let bar = progress::bar(total, action);
+        let this = &*self;
+        let hash = hash.as_str();
+        let results: Vec<(String, Result<()>)> = stream::iter(items)
+            .map(|item| async move {
+                let result = this.set_favorite(hash, &item, starred).await;
+                (item.stat.question_title_slug, result)
+            })
+            .buffer_unordered(BATCH_CONCURRENCY)
+            .inspect(|_| bar.inc(1))
+            .collect()
+            .await;
+        bar.finish_and_clear();
+        self.clear_problems_cache();
+
+        let failures: Vec<(String, LeetUpError)> = results
+            .into_iter()
+            .filter_map(|(slug, result)| result.err().map(|e| (slug, e)))
+            .collect();
+        println!(
+            "{} {} problems",
+            Color::Green(done).make(),
+            total - failures.len()
+        );
+        if failures.is_empty() {
+            return Ok(());
+        }
+        for (slug, e) in &failures {
+            println!("{} {}: {}", Color::Red("Failed").make(), slug, e);
+        }
+        Err(LeetUpError::Any(anyhow!(
+            "{} of {} problems could not be {}",
+            failures.len(),
+            total,
+            done.to_lowercase()
+        )))
     }
-}
 
-impl<'a> Leetcode<'a> {
-    pub fn new(session: Option<&'a Session>, config: &'a Config, cache: KvStore) -> Result<Self> {
-        let name = "leetcode";
-
-        Ok(Leetcode {
-            session,
-            config,
-            cache,
-            name,
-            remote_client: RemoteClient::new(config, session),
-        })
-    }
+    /// Hash of the user's default `Favorite` list.
+    async fn favorite_hash(&self) -> Result<String> {
+        let response: FavoritesResponse = self
+            .remote_client
+            .get(&self.config.urls.favorites, None, self.session())
+            .await?
+            .json()
+            .await?;
 
-    fn is_user_logged_in(&self) -> bool {
-        self.cache.has_key(CacheKey::Session.into())
+        response
+            .favorites
+            .private_favorites
+            .into_iter()
+            .find(|list| list.name == "Favorite")
+            .map(|list| list.id_hash)
+            .ok_or_else(|| LeetUpError::Any(anyhow!("Favorite list not found")))
     }
 
-    fn cache_session(&mut self, session: Session) -> Result<()> {
-        let session_str = serde_json::to_string(&session)?;
-        self.cache.set(CacheKey::Session.into(), session_str)?;
-        // remove key `problems`, rebuild problems cache.
-        //
-        // NOTE: cache.remove throws "Key not found" error
-        // so ignore that error if it is thrown.
-        if self.cache.remove(CacheKey::Problems.into()).is_err() {}
-        Ok(())
+    /// Print what `run_code` would send to `url`, for `--dry-run`.
+    fn print_dry_run(&self, url: &str, problem: &Problem) {
+        let code = problem
+            .typed_code
+            .as_deref()
+            .and_then(parse_code)
+            .unwrap_or_default();
+        println!("Problem:  {} ({})", problem.id, problem.slug);
+        println!("Language: {}", problem.lang);
+        println!("Endpoint: {}", url.replace("$slug", &problem.slug));
+        println!("Code:\n{}", code);
     }
 
-    pub async fn fetch_problems(&mut self) -> Result<Vec<StatStatusPair>> {
-        let problems = self.fetch_all_problems().await?;
-        let problems: Vec<StatStatusPair> =
-            serde_json::from_value(problems["stat_status_pairs"].clone())?;
-
-        Ok(problems)
+    /// Problem `id` solved in `lang`, with the whole of stdin as its code.
+    ///
+    /// Only exact ids and slugs are accepted, since stdin can't be used
+    /// to choose between candidates.
+    async fn stdin_problem(&mut self, id: &str, lang: &Lang) -> Result<Problem> {
+        let mut typed_code = String::new();
+        stdin().read_to_string(&mut typed_code)?;
+        let item = self
+            .fetch_problems()
+            .await?
+            .into_iter()
+            .find(|item| is_problem(item, id))
+            .ok_or_else(|| LeetUpError::ProblemNotFound(id.to_string()))?;
+        let slug = item.stat.question_title_slug;
+
+        Ok(Problem {
+            id: item.stat.frontend_question_id,
+            link: format!("{}{}/submissions/", self.config.urls.problems, slug),
+            slug,
+            lang: lang.slug().to_string(),
+            typed_code: Some(typed_code),
+            difficulty: DifficultyType::from(&item.difficulty),
+        })
     }
 
     async fn run_code(&self, url: &str, problem: &Problem, body: Value) -> Result<Value> {
@@ -351,19 +1675,12 @@ impl<'a> Leetcode<'a> {
             .await
     }
 
-    async fn verify_run_code(&self, url: &str) -> Result<Value> {
-        loop {
-            let response = self
-                .remote_client
-                .get(url, None, self.session())
-                .await?
-                .json::<Value>()
-                .await?;
-            if response["state"] == "SUCCESS" {
-                return Ok(response);
-            }
-            std::thread::sleep(std::time::Duration::from_millis(200));
-        }
+    /// Poll the judge at `url` until it returns the verdict of `id`.
+    async fn verify_run_code(&self, url: &str, id: &str, settings: PollSettings) -> Result<Value> {
+        judge::poll(id, settings, interrupt::is_cancelled, || {
+            self.remote_client.get_json(url, self.session())
+        })
+        .await
     }
 
     fn write_code_fragment(
@@ -374,11 +1691,8 @@ impl<'a> Leetcode<'a> {
         pos: InjectPosition,
     ) -> Result<()> {
         if let Some(either) = code_fragment {
-            let inject_code_pos_pattern = format!(
-                "\n{} {}\n",
-                comment,
-                Pattern::InjectCodePosition(pos).to_string()
-            );
+            let inject_code_pos_pattern =
+                format!("\n{} {}\n", comment, Pattern::InjectCodePosition(pos));
             buf.push_str(&inject_code_pos_pattern);
             let code_fragment = either.to_string();
             buf.push_str(&code_fragment);
@@ -387,12 +1701,16 @@ impl<'a> Leetcode<'a> {
         Ok(())
     }
 
-    fn logout(&mut self) -> Result<()> {
-        if self.cache.remove(CacheKey::Session.into()).is_err() {
-            println!("User not logged in!");
-            return Ok(());
-        }
-        if self.cache.remove(CacheKey::Problems.into()).is_err() {}
+    /// Remove persisted session along with the problems cached for that user.
+    ///
+    /// Logging out without a session is a no-op.
+    pub(crate) fn logout(&mut self) -> Result<()> {
+        // NOTE: cache.remove throws "Key not found" error
+        // so ignore that error if it is thrown.
+        let _ = self.cache.remove(CacheKey::Session.into());
+        self.clear_problems_cache();
+        self.session = None;
+        self.remote_client = RemoteClient::new(self.config, None);
         Ok(())
     }
 
@@ -407,17 +1725,24 @@ impl<'a> Leetcode<'a> {
         Ok(())
     }
 
-    fn pick_hook(&self, content: &str, problem: &Problem, lang: &LangInfo) -> Result<()> {
-        let mut curr_dir = env::current_dir()?;
+    /// Write the generated `content`, running the configured hooks, and
+    /// return the path it was written to.
+    ///
+    /// An existing file is only replaced with `force`, see `write_content`.
+    fn pick_hook(
+        &self,
+        content: &str,
+        problem: &Problem,
+        lang: &LangInfo,
+        force: bool,
+    ) -> Result<PathBuf> {
+        let curr_dir = self.solution_dir(lang)?;
         let mut filename = curr_dir.clone();
         let cfg = self.config()?;
         if let Some(ref cfg) = cfg.pick_hook {
             if let Some(hook_cfg) = cfg.get(&lang.name) {
-                if let Some(dir) = hook_cfg.working_dir() {
-                    let dir = shellexpand::tilde(dir);
-                    curr_dir = PathBuf::from(dir.deref());
+                if hook_cfg.working_dir().is_some() {
                     fs::create_dir_all(&curr_dir)?;
-                    filename = curr_dir.clone();
                 }
                 if let Some(pre) = hook_cfg.script_pre_generation() {
                     println!(
@@ -427,7 +1752,9 @@ impl<'a> Leetcode<'a> {
                     let cmd = pre.to_string();
                     self.execute_script(&cmd, problem, &curr_dir)?;
                 }
-                self.write_content(&mut filename, problem, lang, content.as_bytes())?;
+                if !self.write_content(&mut filename, problem, lang, content, force)? {
+                    return Ok(filename);
+                }
 
                 if let Some(post) = hook_cfg.script_post_generation() {
                     println!(
@@ -445,34 +1772,166 @@ impl<'a> Leetcode<'a> {
                     Color::Magenta(filename.to_str().ok_or(LeetUpError::OptNone)?).make(),
                     Color::Yellow("Note: File path can be wrong if you used: `mkdir`, `cd`, `mv` to move around the generated file. Find the right path used in your script!").make()
                 );
-                return Ok(());
+                return Ok(filename);
             }
         }
-        self.write_content(&mut filename, problem, lang, content.as_bytes())?;
+        if !self.write_content(&mut filename, problem, lang, content, force)? {
+            return Ok(filename);
+        }
         println!(
             "Generated: {}",
             Color::Magenta(filename.to_str().ok_or(LeetUpError::OptNone)?).make()
         );
 
-        Ok(())
+        Ok(filename)
+    }
+
+    /// Append `log` to the `.testlog` file of `problem`, in `test_log_dir`
+    /// or next to `solution`, returning its path.
+    fn append_test_log(&self, solution: &Path, problem: &Problem, log: &str) -> Result<PathBuf> {
+        let dir = match self.config.test_log_dir {
+            Some(ref dir) => PathBuf::from(shellexpand::tilde(dir).deref()),
+            None => solution.parent().map(Path::to_path_buf).unwrap_or_default(),
+        };
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{:04}-{}.testlog", problem.id, problem.slug));
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        file.write_all(log.as_bytes())?;
+
+        Ok(path)
+    }
+
+    /// Directory `pick` writes `lang` solutions to: the pick hook's working
+    /// directory if configured, the current directory otherwise.
+    fn solution_dir(&self, lang: &LangInfo) -> Result<PathBuf> {
+        let working_dir = self
+            .config
+            .pick_hook
+            .as_ref()
+            .and_then(|hooks| hooks.get(&lang.name))
+            .and_then(|hook| hook.working_dir());
+        match working_dir {
+            Some(dir) => Ok(PathBuf::from(shellexpand::tilde(dir).deref())),
+            None => Ok(env::current_dir()?),
+        }
+    }
+
+    /// File name of `problem`'s solution, relative to `solution_dir`.
+    fn solution_filename(&self, problem: &Problem, lang: &LangInfo) -> Result<PathBuf> {
+        let template = self
+            .config
+            .filename_template
+            .as_deref()
+            .unwrap_or(DEFAULT_FILENAME_TEMPLATE);
+        render_filename(template, problem, &lang.extension)
     }
 
+    /// Path of the file `pick` generates for `item` in `lang`, the
+    /// configured language by default.
+    fn picked_solution_path(&self, item: &StatStatusPair, lang: Option<&Lang>) -> Result<PathBuf> {
+        let lang = lang.map(|l| l.info()).unwrap_or(self.config.lang.info());
+        let problem = self.new_problem(item, &lang);
+        Ok(self
+            .solution_dir(&lang)?
+            .join(self.solution_filename(&problem, &lang)?))
+    }
+
+    /// Latest accepted `lang` submission of `slug`, if any.
+    async fn last_accepted(&self, slug: &str, lang: &str) -> Result<Option<SubmissionDump>> {
+        let url = self.config.urls.submissions.replace("$slug", slug);
+        let mut offset = 0;
+        loop {
+            let page: SubmissionList = self
+                .remote_client
+                .get_json(
+                    &format!("{}?offset={}&limit={}", url, offset, SUBMISSIONS_PAGE_SIZE),
+                    self.session(),
+                )
+                .await?;
+            let count = page.submissions_dump.len();
+            let accepted = page
+                .submissions_dump
+                .into_iter()
+                .find(|submission| submission.is_accepted() && submission.lang == lang);
+            if accepted.is_some() || !page.has_next || count == 0 {
+                return Ok(accepted);
+            }
+            offset += count;
+        }
+    }
+
+    /// Write `content` to the file named after `problem` in `filename`,
+    /// returning whether anything was written.
+    ///
+    /// An existing file is kept unless `force` is set. If it still has its
+    /// `@leetup=code` markers, the user is asked whether to write the new
+    /// boilerplate around the code they wrote instead.
     fn write_content(
         &self,
         filename: &mut PathBuf,
         problem: &Problem,
         lang: &LangInfo,
-        content: &[u8],
-    ) -> Result<()> {
-        filename.push(&problem.slug);
-        filename.set_extension(&lang.extension);
+        content: &str,
+        force: bool,
+    ) -> Result<bool> {
+        filename.push(self.solution_filename(problem, lang)?);
+        if let Some(dir) = filename.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut content = content.to_string();
+        if !force && filename.exists() {
+            let existing = fs::read_to_string(&filename)?;
+            let path = filename.display().to_string();
+            let merged = extract_marked_code(&existing)
+                .and_then(|code| replace_marked_code(&content, &code));
+            match merged {
+                Some(merged)
+                    if confirm(&format!(
+                        "{} exists, update its boilerplate and keep your code?",
+                        path
+                    ))? =>
+                {
+                    content = merged;
+                }
+                _ => {
+                    println!(
+                        "{} {}\n{}",
+                        Color::Yellow("Exists:").make(),
+                        Color::Magenta(&path).make(),
+                        Color::Yellow("Use --force to overwrite it").make()
+                    );
+                    return Ok(false);
+                }
+            }
+        }
 
         let mut file = File::create(&filename)?;
-        file.write_all(content)?;
-        Ok(())
+        file.write_all(content.as_bytes())?;
+        Ok(true)
+    }
+
+    /// Fetch the problems of each category concurrently and merge them.
+    async fn fetch_category_problems(
+        &self,
+        categories: &[cmd::Category],
+    ) -> Result<Vec<StatStatusPair>> {
+        let session = self.session();
+        let lists = futures::future::try_join_all(categories.iter().map(|category| async move {
+            let url = self.config.urls.problems(category.slug());
+            self.remote_client
+                .get_json::<ListResponse>(&url, session)
+                .await
+        }))
+        .await?;
+
+        Ok(merge_categories(lists))
     }
 
-    async fn get_problems_with_topic_tag(&self, tag: &str) -> Result<Value> {
+    async fn get_topic_tag_questions(&self, tag: &str) -> Result<Vec<TopicTagQuestion>> {
         let query = r#"
             query getTopicTag($slug: String!) {
                  topicTag(slug: $slug) {
@@ -485,31 +1944,90 @@ impl<'a> Leetcode<'a> {
                      titleSlug
                      questionFrontendId
                      status
+                     acRate
                    }
                  }
              }
         "#;
-        let body: Value = json!({
-            "operationName": "getTopicTag",
-            "variables": {
-                "slug": tag,
-            },
-            "query": query
-        });
+        let data: TopicTagData = self
+            .remote_client
+            .graphql("getTopicTag", query, json!({ "slug": tag }))
+            .await?;
 
-        self.remote_client
-            .post(&self.config.urls.graphql, &body, || None)
-            .await
+        data.topic_tag
+            .map(|topic_tag| topic_tag.questions)
+            .ok_or_else(|| {
+                LeetUpError::Any(anyhow!(
+                    "Unknown tag `{}`, use the tag slug e.g. `dynamic-programming`",
+                    tag
+                ))
+            })
+    }
+
+    /// Fetch problems asked by a company, cached per company for `cache_ttl`.
+    async fn get_company_questions(&mut self, company: &str) -> Result<Vec<TopicTagQuestion>> {
+        self.require_session()?;
+        let cached =
+            if self.config.offline || self.is_cache_fresh(CacheKey::CompanyFetchedAt(company))? {
+                self.cache.get(self.cache_key(CacheKey::Company(company)))?
+            } else {
+                None
+            };
+        if let Some(ref val) = cached {
+            info!("Company `{}` cache hit", company);
+            return Ok(serde_json::from_str(val)?);
+        }
+
+        let query = r#"
+            query getCompanyTag($slug: String!) {
+                 companyTag(slug: $slug) {
+                   name
+                   slug
+                   questions {
+                     difficulty
+                     isPaidOnly
+                     title
+                     titleSlug
+                     questionFrontendId
+                     status
+                     acRate
+                   }
+                 }
+             }
+        "#;
+        let data: CompanyTagData = self
+            .remote_client
+            .graphql("getCompanyTag", query, json!({ "slug": company }))
+            .await?;
+        let questions = data.company_tag.map(|tag| tag.questions).ok_or_else(|| {
+            LeetUpError::Any(anyhow!(
+                "Unknown company `{}` or premium subscription required",
+                company
+            ))
+        })?;
+        self.cache.set(
+            self.cache_key(CacheKey::Company(company)),
+            serde_json::to_string(&questions)?,
+        )?;
+        self.cache.set(
+            self.cache_key(CacheKey::CompanyFetchedAt(company)),
+            unix_now().to_string(),
+        )?;
+
+        Ok(questions)
     }
 
+    /// Write the code stub of `problem`, returning its path unless the
+    /// problem has no code definitions.
     fn generate_problem_stub(
         &mut self,
         lang: &LangInfo,
         problem: &Problem,
         problem_id: usize,
         slug: String,
-        response: &Value,
-    ) -> Result<()> {
+        detail: &QuestionDetail,
+        force: bool,
+    ) -> Result<Option<PathBuf>> {
         let mut definition = None;
         let mut start_comment = "";
         let line_comment;
@@ -540,9 +2058,8 @@ impl<'a> Leetcode<'a> {
             _ => unreachable!(),
         };
 
-        if let Some(content) = &response["data"]["question"]["content"].as_str() {
-            let content = from_read(content.as_bytes(), 80);
-            let content = content.replace("**", "");
+        if let Some(content) = &detail.content {
+            let content = html::to_text(content, 80);
             let content = content
                 .split('\n')
                 .map(|s| format!("{} {}", line_comment, s))
@@ -550,9 +2067,8 @@ impl<'a> Leetcode<'a> {
                 .join("\n");
             info!("Single Comment: {}", single_comment);
 
-            let pattern_custom = format!("{} {}", single_comment, Pattern::CustomCode.to_string());
-            let pattern_leetup_info =
-                format!("{} {}", single_comment, Pattern::LeetUpInfo.to_string());
+            let pattern_custom = format!("{} {}", single_comment, Pattern::CustomCode);
+            let pattern_leetup_info = format!("{} {}", single_comment, Pattern::LeetUpInfo);
             let content = format!(
                 "{}\n{} id={} lang={} slug={}\n\n{}\n{}\n{}\n{}",
                 pattern_custom,
@@ -569,24 +2085,21 @@ impl<'a> Leetcode<'a> {
             definition = Some(content);
         }
 
-        let mut filename = env::current_dir()?;
-        filename.push(slug);
-        filename.set_extension(&lang.extension);
-
-        if let Some(code_defs) = &response["data"]["question"]["codeDefinition"].as_str() {
+        if let Some(code_defs) = &detail.code_definition {
             let mut buf = String::new();
             let code_defs: HashMap<_, _> = serde_json::from_str::<Vec<CodeDefinition>>(code_defs)?
                 .into_iter()
                 .map(|def| (def.value.to_owned(), def))
-                .into_iter()
                 .collect();
             if let Some(ref definition) = definition {
                 buf.push_str(definition)
             }
-            let pattern_code = format!("\n{} {}\n", single_comment, Pattern::Code.to_string());
+            let pattern_code = format!("\n{} {}\n", single_comment, Pattern::Code);
             let code = &code_defs
                 .get(&lang.name)
-                .ok_or(LeetUpError::OptNone)?
+                .ok_or_else(|| {
+                    LeetUpError::LangNotAvailable(problem.slug.to_owned(), lang.name.to_owned())
+                })?
                 .default_code;
             debug!("Code: {}", code);
             let inject_code = self
@@ -624,16 +2137,17 @@ impl<'a> Leetcode<'a> {
                 )?;
             }
 
-            self.pick_hook(&buf, problem, lang)?;
+            return self.pick_hook(&buf, problem, lang, force).map(Some);
         }
 
-        Ok(())
+        Ok(None)
     }
 
     /*
      * Parse Option<Option<String>> from structopt
      *
-     * Get string from command line if provided, otherwise try to get string from stdin
+     * Get string from command line if provided, otherwise try to get string from stdin.
+     * Falls back to the problem's sample test case when `-t` is not used at all.
      *
      * We can provide test data as multiline input using stdin.
      *
@@ -646,14 +2160,170 @@ impl<'a> Leetcode<'a> {
      * END
      * ```
      */
-    fn get_test_data(&self, test_data: Option<Option<String>>) -> String {
-        test_data.unwrap().unwrap_or_else(|| {
-            let mut buf = String::new();
-            stdin()
-                .lock()
-                .read_to_string(&mut buf)
-                .expect("test input expected from stdin");
-            buf
+    async fn get_test_data(
+        &self,
+        test_data: Option<Option<String>>,
+        problem: &Problem,
+    ) -> Result<String> {
+        match test_data {
+            Some(Some(data)) => Ok(data),
+            Some(None) => {
+                let mut buf = String::new();
+                stdin().lock().read_to_string(&mut buf)?;
+                Ok(buf)
+            }
+            None => self.get_sample_test_case(&problem.slug).await,
+        }
+    }
+
+    /// Let the user edit the last custom test cases of `problem`, or its
+    /// sample test case the first time, in a temporary file.
+    async fn edit_test_data(&mut self, problem: &Problem) -> Result<String> {
+        let cached = self
+            .cache
+            .get(self.cache_key(CacheKey::TestCase(&problem.slug)))?;
+        let test_data = match cached {
+            Some(test_data) => test_data,
+            None => self.get_sample_test_case(&problem.slug).await?,
+        };
+
+        // Removed when `file` is dropped, after the edited content is read back.
+        let file = tempfile::Builder::new()
+            .prefix(&format!("leetup-{}-", problem.slug))
+            .suffix("-testcase.txt")
+            .tempfile()?;
+        fs::write(file.path(), test_data)?;
+        editor::edit(file.path())?;
+
+        Ok(fs::read_to_string(file.path())?)
+    }
+
+    async fn get_sample_test_case(&self, slug: &str) -> Result<String> {
+        let query = r#"
+            query getQuestionDetail($titleSlug: String!) {
+               question(titleSlug: $titleSlug) {
+                 sampleTestCase
+               }
+            }
+        "#;
+        let data: QuestionData = self
+            .remote_client
+            .graphql("getQuestionDetail", query, json!({ "titleSlug": slug }))
+            .await?;
+
+        data.question
+            .and_then(|question| question.sample_test_case)
+            .ok_or_else(|| {
+                LeetUpError::Any(anyhow!("No sample test case found, use -t to provide one"))
+            })
+    }
+}
+
+/// Print all `hints` numbered, or only hint `nth` when given.
+fn print_hints(hints: &[String], nth: Option<usize>, slug: &str) -> Result<()> {
+    if hints.is_empty() {
+        println!(
+            "{}",
+            Color::Yellow(&format!("Problem `{}` has no hints", slug)).make()
+        );
+        return Ok(());
+    }
+
+    let selected: Vec<(usize, &String)> = match nth {
+        Some(n) => {
+            let hint = n.checked_sub(1).and_then(|i| hints.get(i)).ok_or_else(|| {
+                LeetUpError::Any(anyhow!(
+                    "Problem `{}` has {} hint(s), pick one from 1 to {}",
+                    slug,
+                    hints.len(),
+                    hints.len()
+                ))
+            })?;
+            vec![(n, hint)]
+        }
+        None => hints
+            .iter()
+            .enumerate()
+            .map(|(i, hint)| (i + 1, hint))
+            .collect(),
+    };
+    for (n, hint) in selected {
+        println!(
+            "Hint {}/{}: {}",
+            n,
+            hints.len(),
+            html::to_text(hint, 80).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Whether `id` is the frontend id or title slug of `item`.
+fn is_problem(item: &StatStatusPair, id: &str) -> bool {
+    item.stat.frontend_question_id.to_string() == id || item.stat.question_title_slug == id
+}
+
+/// Seed from `LEETUP_RANDOM_SEED` if set, so picks can be reproduced.
+fn random_generator() -> StdRng {
+    match env::var(RANDOM_SEED_ENV).ok().and_then(|s| s.parse().ok()) {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Error for a `keyword` matching several `candidates` with no terminal to
+/// ask which one was meant, e.g. in scripts.
+fn ambiguous_problem(keyword: &str, candidates: &[StatStatusPair]) -> LeetUpError {
+    const SHOWN: usize = 10;
+    let mut matches: Vec<String> = candidates
+        .iter()
+        .take(SHOWN)
+        .map(|item| {
+            format!(
+                "  {} {}",
+                item.stat.frontend_question_id, item.stat.question_title_slug
+            )
         })
+        .collect();
+    if candidates.len() > SHOWN {
+        matches.push(format!("  ... and {} more", candidates.len() - SHOWN));
+    }
+
+    LeetUpError::Any(anyhow!(
+        "`{}` matches several problems, use an exact ID or title slug:\n{}",
+        keyword,
+        matches.join("\n")
+    ))
+}
+
+/// Ask a yes/no question on stdin, `no` when stdin is not a terminal.
+fn confirm(question: &str) -> Result<bool> {
+    if !stdin().is_terminal() {
+        return Ok(false);
+    }
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Refuse a `submit --lang` override whose extension disagrees with `path`,
+/// unless `force` is set.
+fn check_lang_override(path: &str, lang: &Lang, force: bool) -> Result<()> {
+    let extension = Path::new(path).extension().and_then(|ext| ext.to_str());
+    if extension.is_none() || extension == Some(lang.extension()) {
+        return Ok(());
+    }
+    if !force {
+        return Err(LeetUpError::LangMismatch {
+            path: path.to_string(),
+            lang: lang.slug().to_string(),
+        });
     }
+    eprintln!("Warning: submitting `{}` as {}", path, lang.slug());
+
+    Ok(())
 }
@@ -5,8 +5,8 @@ pub use session::*;
 
 pub mod auth;
 mod file;
+pub mod judge;
 mod lang;
 pub mod leetcode;
-mod pool;
 mod provider;
 mod session;
@@ -1,16 +1,13 @@
-use std::cmp::Ordering;
-
-use ansi_term::Colour::{Green, Red, Yellow};
 use async_trait::async_trait;
-use leetup_cache::kvstore::KvStore;
 
-use crate::model::DifficultyType::{Easy, Hard, Medium};
-use crate::model::{DifficultyType, ProblemInfo};
+use crate::model::{
+    difficulty_footer, problem_row, problem_table, title_width, DifficultyType, ProblemInfo,
+    ProblemSummary,
+};
 use crate::service::Session;
 use crate::{
-    cmd::{self, OrderBy, Query, User},
-    icon::Icon,
-    Config, Result,
+    cmd::{self, OutputFormat, User},
+    pager, Config, Result,
 };
 
 /// ServiceProvider trait provides all the functionalities required to solve problems
@@ -21,121 +18,215 @@ pub trait ServiceProvider<'a> {
     fn config(&self) -> Result<&Config>;
     async fn fetch_all_problems(&mut self) -> Result<serde_json::value::Value>;
     async fn list_problems(&mut self, list: cmd::List) -> Result<()>;
+    #[cfg(feature = "tui")]
+    async fn browse_problems(&mut self, list: cmd::List) -> Result<()>;
+    async fn random_problem(&mut self, random: cmd::Random) -> Result<()>;
+    async fn next_problem(&mut self, next: cmd::Next) -> Result<()>;
+    async fn daily_problem(&mut self, daily: cmd::Daily) -> Result<()>;
     async fn pick_problem(&mut self, pick: cmd::Pick) -> Result<()>;
-    async fn problem_test(&self, test: cmd::Test) -> Result<()>;
-    async fn problem_submit(&self, submit: cmd::Submit) -> Result<()>;
+    async fn problem_stats(&mut self, stats: cmd::Stats) -> Result<()>;
+    async fn show_problem(&mut self, show: cmd::Show) -> Result<()>;
+    async fn open_problem(&mut self, open: cmd::Open) -> Result<()>;
+    async fn problem_info(&mut self, info: cmd::Info) -> Result<()>;
+    async fn similar_problems(&mut self, similar: cmd::Similar) -> Result<()>;
+    async fn tags_report(&mut self, report: cmd::TagsReport) -> Result<()>;
+    async fn export_problems(&mut self, export: cmd::Export) -> Result<()>;
+    async fn problem_note(&mut self, note: cmd::Note) -> Result<()>;
+    async fn star_problem(&mut self, star: cmd::Star, starred: bool) -> Result<()>;
+    async fn problem_test(&mut self, test: cmd::Test) -> Result<()>;
+    async fn problem_submit(&mut self, submit: cmd::Submit) -> Result<()>;
+    async fn submission_result(&mut self, result: cmd::SubmissionResult) -> Result<()>;
+    async fn diff_solution(&mut self, diff: cmd::Diff) -> Result<()>;
     async fn process_auth(&mut self, user: User) -> Result<()>;
-    fn cache(&mut self) -> Result<&KvStore>;
-    fn name(&self) -> &'a str;
+    async fn whoami(&mut self) -> Result<()>;
+
+    /// Print list of problems in the requested output format, paging tables
+    /// taller than the terminal if `pager` is set.
+    ///
+    /// Tables only show `fields` when given, otherwise the default columns.
+    fn print_list(
+        probs: &[Box<dyn ProblemInfo + Send>],
+        output: OutputFormat,
+        fields: Option<&[&str]>,
+        pager: bool,
+    ) -> Result<()> {
+        match output {
+            OutputFormat::Table => {
+                let rows = match fields {
+                    Some(fields) => problem_table(probs, fields),
+                    None => {
+                        let width = title_width();
+                        probs
+                            .iter()
+                            .map(|prob| problem_row(prob.as_ref(), width))
+                            .collect()
+                    }
+                };
+                let mut table: String = rows.into_iter().map(|row| row + "\n").collect();
+                table.push_str(&difficulty_footer(probs));
+                table.push('\n');
+                pager::page(&table, pager)?;
+            }
+            OutputFormat::Json => Self::json_list(probs)?,
+            OutputFormat::Csv => Self::csv_list(probs),
+        }
+
+        Ok(())
+    }
 
     /// Print list of problems properly.
-    fn pretty_list<T: IntoIterator<Item = &'a Box<dyn ProblemInfo + Send>>>(probs: T) {
+    fn pretty_list(probs: &[Box<dyn ProblemInfo + Send>]) {
+        let width = title_width();
         for prob in probs {
-            let is_favorite = if let Some(is_favor) = prob.is_favorite() {
-                is_favor
-            } else {
-                false
-            };
-            let starred_icon = if is_favorite {
-                Yellow.paint(Icon::Star.to_string()).to_string()
-            } else {
-                Icon::Empty.to_string()
-            };
-
-            let locked_icon = if prob.is_paid_only() {
-                Red.paint(Icon::Lock.to_string()).to_string()
-            } else {
-                Icon::Empty.to_string()
-            };
-
-            let acd = if prob.status().is_some() {
-                Green.paint(Icon::Yes.to_string()).to_string()
-            } else {
-                Icon::Empty.to_string()
-            };
-
-            println!(
-                "{} {:2} {} [{:^4}] {:75} {:6}",
-                starred_icon,
-                locked_icon,
-                acd,
-                prob.question_id(),
-                prob.question_title(),
-                prob.difficulty().to_string()
-            );
+            println!("{}", problem_row(prob.as_ref(), width));
         }
     }
 
-    /// Filter problems using multiple queries.
-    fn apply_queries(queries: &Vec<Query>, o: &Box<dyn ProblemInfo + Send>) -> bool {
-        let mut is_satisfied = true;
-        let difficulty: DifficultyType = o.difficulty().into();
-        let is_favorite = if let Some(is_favor) = o.is_favorite() {
-            is_favor
-        } else {
-            false
-        };
-
-        for q in queries {
-            match q {
-                Query::Easy => is_satisfied &= difficulty == Easy,
-                Query::NotEasy => is_satisfied &= difficulty != Easy,
-                Query::Medium => is_satisfied &= difficulty == Medium,
-                Query::NotMedium => is_satisfied &= difficulty != Medium,
-                Query::Hard => is_satisfied &= difficulty == Hard,
-                Query::NotHard => is_satisfied &= difficulty != Hard,
-                Query::Locked => is_satisfied &= o.is_paid_only(),
-                Query::Unlocked => is_satisfied &= !o.is_paid_only(),
-                Query::Done => is_satisfied &= o.status().is_some(),
-                Query::NotDone => is_satisfied &= o.status().is_none(),
-                Query::Starred => is_satisfied &= is_favorite,
-                Query::Unstarred => is_satisfied &= !is_favorite,
-            }
-        }
+    /// Print list of problems as JSON.
+    fn json_list(probs: &[Box<dyn ProblemInfo + Send>]) -> Result<()> {
+        let summaries: Vec<ProblemSummary> = probs
+            .iter()
+            .map(|prob| ProblemSummary::from(prob.as_ref()))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&summaries)?);
 
-        is_satisfied
+        Ok(())
     }
 
-    /// Order problems by Id, Title, Difficulty in Ascending or Descending order
-    fn with_ordering(
-        orders: &[OrderBy],
-        a: &Box<dyn ProblemInfo + Send>,
-        b: &Box<dyn ProblemInfo + Send>,
-    ) -> Ordering {
-        let mut ordering = Ordering::Equal;
-        let id_ordering = a.question_id().cmp(&b.question_id());
-        let title_ordering = a.question_title().cmp(&b.question_title());
-        let a_difficulty_level: DifficultyType = a.difficulty().into();
-        let b_difficulty_level: DifficultyType = b.difficulty().into();
-        let diff_ordering = a_difficulty_level.cmp(&b_difficulty_level);
-
-        for order in orders {
-            match order {
-                OrderBy::IdAsc => ordering = ordering.then(id_ordering),
-                OrderBy::IdDesc => ordering = ordering.then(id_ordering.reverse()),
-                OrderBy::TitleAsc => ordering = ordering.then(title_ordering),
-                OrderBy::TitleDesc => ordering = ordering.then(title_ordering.reverse()),
-                OrderBy::DifficultyAsc => ordering = ordering.then(diff_ordering),
-                OrderBy::DifficultyDesc => ordering = ordering.then(diff_ordering.reverse()),
-            }
+    /// Print list of problems as CSV with a header row.
+    fn csv_list(probs: &[Box<dyn ProblemInfo + Send>]) {
+        println!("id,title,slug,difficulty,paid_only,status,acceptance,category");
+        for prob in probs {
+            let difficulty: DifficultyType = prob.difficulty().into();
+            let acceptance = prob
+                .acceptance()
+                .map(|acceptance| format!("{:.2}", acceptance))
+                .unwrap_or_default();
+            println!(
+                "{},{},{},{},{},{},{},{}",
+                prob.question_id(),
+                csv_field(prob.question_title()),
+                csv_field(prob.slug()),
+                difficulty,
+                prob.is_paid_only(),
+                csv_field(prob.status().unwrap_or_default()),
+                acceptance,
+                csv_field(prob.category().unwrap_or_default())
+            );
         }
+    }
+}
 
-        ordering
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
     }
 }
 
 pub enum CacheKey<'a> {
     Session,
-    Problems,
+    ProblemsFetchedAt,
+    ProblemsETag,
+    ProblemsHash,
     Problem(&'a str),
+    TestCase(&'a str),
+    Company(&'a str),
+    CompanyFetchedAt(&'a str),
+    Tags(&'a str),
+}
+
+impl CacheKey<'_> {
+    /// Key prefixed with `namespace`, see `cache_namespace`.
+    ///
+    /// The session itself is shared by all namespaces since it decides
+    /// which user namespace is used.
+    pub fn namespaced(self, namespace: &str) -> String {
+        match self {
+            CacheKey::Session => self.into(),
+            key => format!("{}/{}", namespace, String::from(key)),
+        }
+    }
+}
+
+/// Namespace of cached problems, made of the site host and a hash of the
+/// session so data of different sites and users is never mixed up.
+/// Anonymous runs use their own namespace.
+pub fn cache_namespace(base_url: &str, session: Option<&Session>) -> String {
+    let host = base_url
+        .split("://")
+        .last()
+        .unwrap_or(base_url)
+        .trim_end_matches('/');
+    match session {
+        Some(session) => format!("{}/user-{:016x}", host, fnv1a(&session.id)),
+        None => format!("{}/anonymous", host),
+    }
+}
+
+/// Hash of a fetched body, to tell whether it changed since it was cached.
+pub fn content_hash(body: &str) -> String {
+    format!("{:016x}", fnv1a(body))
 }
 
-impl<'a> From<CacheKey<'_>> for String {
+/// FNV-1a hash, stable across Rust releases unlike `DefaultHasher`.
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl From<CacheKey<'_>> for String {
     fn from(key: CacheKey) -> Self {
         match key {
             CacheKey::Session => "session".to_string(),
-            CacheKey::Problems => "problems".to_string(),
+            CacheKey::ProblemsFetchedAt => "problems_fetched_at".to_string(),
+            CacheKey::ProblemsETag => "problems_etag".to_string(),
+            CacheKey::ProblemsHash => "problems_hash".to_string(),
             CacheKey::Problem(id) => format!("problem_{}", id),
+            CacheKey::TestCase(slug) => format!("testcase_{}", slug),
+            CacheKey::Company(slug) => format!("company_{}", slug),
+            CacheKey::CompanyFetchedAt(slug) => format!("company_fetched_at_{}", slug),
+            CacheKey::Tags(slug) => format!("tags_{}", slug),
         }
     }
 }
+
+#[test]
+fn test_csv_field() {
+    assert_eq!(csv_field("Two Sum"), "Two Sum");
+    assert_eq!(csv_field("Pow(x, n)"), "\"Pow(x, n)\"");
+    assert_eq!(csv_field("Say \"Hi\""), "\"Say \"\"Hi\"\"\"");
+}
+
+#[test]
+fn test_cache_namespace() {
+    let alice = Session::new("alice-session".into(), "csrf".into());
+    let bob = Session::new("bob-session".into(), "csrf".into());
+    let us = "https://leetcode.com";
+    let cn = "https://leetcode.cn";
+
+    let problems =
+        |base, session| CacheKey::ProblemsFetchedAt.namespaced(&cache_namespace(base, session));
+    assert_eq!(
+        problems(us, None),
+        "leetcode.com/anonymous/problems_fetched_at"
+    );
+    assert!(problems(us, Some(&alice)).starts_with("leetcode.com/user-"));
+    assert_ne!(problems(us, Some(&alice)), problems(us, Some(&bob)));
+    assert_ne!(problems(us, Some(&alice)), problems(cn, Some(&alice)));
+    assert_eq!(problems(us, Some(&alice)), problems(us, Some(&alice)));
+    assert_eq!(
+        CacheKey::Session.namespaced(&cache_namespace(us, Some(&alice))),
+        "session"
+    );
+}
+
+#[test]
+fn test_content_hash() {
+    assert_eq!(content_hash(""), "cbf29ce484222325");
+    assert_eq!(content_hash("{}"), content_hash("{}"));
+    assert_ne!(content_hash("{}"), content_hash("[]"));
+}
@@ -1,7 +1,23 @@
+use std::fs;
+use std::path::Path;
 use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use anyhow::anyhow;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use cookie::Cookie;
+use reqwest::header::HeaderValue;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{LeetUpError, Result};
+
+const SESSION_COOKIE: &str = "LEETCODE_SESSION";
+const CSRF_COOKIE: &str = "csrftoken";
+
+/// Environment variables overriding the stored session, e.g. in CI.
+pub const SESSION_ENV: &str = "LEETCODE_SESSION";
+pub const CSRF_ENV: &str = "LEETCODE_CSRF";
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Session {
@@ -13,6 +29,131 @@ impl Session {
     pub fn new(id: String, csrf: String) -> Self {
         Session { id, csrf }
     }
+
+    /// Session from `LEETCODE_SESSION` and `LEETCODE_CSRF`, if set.
+    ///
+    /// Setting only one of them is an error.
+    pub fn from_env() -> Result<Option<Self>> {
+        let var = |name| std::env::var(name).ok();
+        Session::from_vars(var(SESSION_ENV), var(CSRF_ENV))
+    }
+
+    fn from_vars(id: Option<String>, csrf: Option<String>) -> Result<Option<Self>> {
+        let id = checked_env(SESSION_ENV, id)?;
+        let csrf = checked_env(CSRF_ENV, csrf)?;
+        match (id, csrf) {
+            (Some(id), Some(csrf)) => Ok(Some(Session::new(id, csrf))),
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(missing_env(CSRF_ENV, SESSION_ENV)),
+            (None, Some(_)) => Err(missing_env(SESSION_ENV, CSRF_ENV)),
+        }
+    }
+
+    /// Read session from a cookies file exported from the browser.
+    ///
+    /// Both Netscape `cookies.txt` and JSON exports are supported.
+    pub fn from_cookie_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let cookies = if content.trim_start().starts_with('[') {
+            parse_json_cookies(&content)?
+        } else {
+            parse_netscape_cookies(&content)
+        };
+
+        Session::from_exported_cookies(&cookies, unix_now())
+    }
+
+    fn from_exported_cookies(cookies: &[ExportedCookie], now: u64) -> Result<Self> {
+        let find = |name: &str| -> Result<String> {
+            let cookie = cookies
+                .iter()
+                .find(|c| c.name == name)
+                .ok_or_else(|| LeetUpError::Any(anyhow!("Cookie `{}` not found", name)))?;
+            match cookie.expires {
+                Some(expires) if expires <= now => Err(LeetUpError::SessionExpired),
+                _ => Ok(cookie.value.to_owned()),
+            }
+        };
+
+        Ok(Session::new(find(SESSION_COOKIE)?, find(CSRF_COOKIE)?))
+    }
+
+    /// Claims of the `LEETCODE_SESSION` token, which is a JWT on
+    /// leetcode.com. `None` when it can't be decoded.
+    pub fn claims(&self) -> Option<SessionClaims> {
+        let payload = self.id.split('.').nth(1)?;
+        let payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+        serde_json::from_slice(&payload).ok()
+    }
+}
+
+/// What the session token says about the user, see `Session::claims`.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct SessionClaims {
+    pub username: Option<String>,
+
+    /// Expiry as unix timestamp.
+    #[serde(rename = "expired_time_")]
+    pub expires: Option<u64>,
+}
+
+/// Cookie as exported by the browser.
+struct ExportedCookie {
+    name: String,
+    value: String,
+
+    /// Expiry as unix timestamp, `None` for session cookies.
+    expires: Option<u64>,
+}
+
+/// Parse Netscape `cookies.txt` format:
+/// `domain  flag  path  secure  expiration  name  value` separated by tabs.
+fn parse_netscape_cookies(content: &str) -> Vec<ExportedCookie> {
+    content
+        .lines()
+        .map(|line| line.trim_start_matches("#HttpOnly_"))
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.trim_end().split('\t').collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            let expires = fields[4].parse::<u64>().ok().filter(|e| *e > 0);
+            Some(ExportedCookie {
+                name: fields[5].to_string(),
+                value: fields[6].to_string(),
+                expires,
+            })
+        })
+        .collect()
+}
+
+/// Parse JSON cookie export: an array of `{ "name", "value", "expirationDate" }`.
+fn parse_json_cookies(content: &str) -> Result<Vec<ExportedCookie>> {
+    let cookies: Vec<Value> = serde_json::from_str(content)?;
+
+    Ok(cookies
+        .iter()
+        .filter_map(|c| {
+            let expires = c["expirationDate"]
+                .as_f64()
+                .or_else(|| c["expires"].as_f64())
+                .map(|e| e as u64);
+            Some(ExportedCookie {
+                name: c["name"].as_str()?.to_string(),
+                value: c["value"].as_str()?.to_string(),
+                expires,
+            })
+        })
+        .collect())
+}
+
+/// Current time as unix timestamp in seconds.
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
 }
 
 impl FromStr for Session {
@@ -29,8 +170,8 @@ impl FromStr for Session {
             let cookie = cookie?;
             let name = cookie.name();
             match name {
-                "LEETCODE_SESSION" => id = cookie.value().to_string(),
-                "csrftoken" => csrf = cookie.value().to_string(),
+                SESSION_COOKIE => id = cookie.value().to_string(),
+                CSRF_COOKIE => csrf = cookie.value().to_string(),
                 _ => (),
             }
         }
@@ -41,8 +182,8 @@ impl FromStr for Session {
 
 fn session_to_cookie(id: &str, csrf: &str) -> String {
     let mut s = String::new();
-    s.push_str(&format!("{}={}; ", "LEETCODE_SESSION", id));
-    s.push_str(&format!("{}={}", "csrftoken", csrf));
+    s.push_str(&format!("{}={}; ", SESSION_COOKIE, id));
+    s.push_str(&format!("{}={}", CSRF_COOKIE, csrf));
 
     s
 }
@@ -59,6 +200,59 @@ impl From<&Session> for String {
     }
 }
 
+/// Trimmed value of the environment variable `name`, `None` when empty.
+///
+/// Values pasted with a stray newline or control character can't be sent
+/// as a header, so they are rejected here instead of failing every request.
+fn checked_env(name: &str, value: Option<String>) -> Result<Option<String>> {
+    let value = match value.as_deref().map(str::trim) {
+        Some(value) if !value.is_empty() => value.to_string(),
+        _ => return Ok(None),
+    };
+    if !value.is_ascii() || HeaderValue::from_str(&value).is_err() {
+        return Err(LeetUpError::Any(anyhow!(
+            "`{}` contains characters that can't be sent in a cookie, check its value",
+            name
+        )));
+    }
+
+    Ok(Some(value))
+}
+
+fn missing_env(missing: &str, set: &str) -> LeetUpError {
+    LeetUpError::Any(anyhow!(
+        "`{}` is set but `{}` is not, set both to use a session from the environment",
+        set,
+        missing
+    ))
+}
+
+#[test]
+fn test_session_from_vars() {
+    let vars = |id: Option<&str>, csrf: Option<&str>| {
+        Session::from_vars(id.map(String::from), csrf.map(String::from))
+    };
+    let session = vars(Some("id"), Some("csrf")).unwrap().unwrap();
+    assert_eq!((session.id.as_str(), session.csrf.as_str()), ("id", "csrf"));
+    assert!(vars(None, None).unwrap().is_none());
+    assert!(vars(Some(""), Some("")).unwrap().is_none());
+    let session = vars(Some(" id\n"), Some("csrf\r\n")).unwrap().unwrap();
+    assert_eq!((session.id.as_str(), session.csrf.as_str()), ("id", "csrf"));
+
+    let err = vars(Some("i\nd"), Some("csrf")).unwrap_err().to_string();
+    assert!(err.contains("`LEETCODE_SESSION` contains"));
+    let err = vars(Some("id"), Some("csrf\u{7f}"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("`LEETCODE_CSRF` contains"));
+    assert!(vars(Some("idé"), Some("csrf")).is_err());
+
+    let err = vars(Some("id"), None).unwrap_err().to_string();
+    assert!(err.contains("`LEETCODE_CSRF` is not"));
+    let err = vars(None, Some("csrf")).unwrap_err().to_string();
+    assert!(err.contains("`LEETCODE_SESSION` is not"));
+}
+
 #[test]
 fn test_cookie_parser() {
     let cookie = "csrftoken=asdsd; LEETCODE_SESSION=asdasd";
@@ -67,3 +261,48 @@ fn test_cookie_parser() {
     assert!(!session.csrf.is_empty());
     assert!(!session.id.is_empty());
 }
+
+#[test]
+fn test_netscape_cookie_file() {
+    let content = "# Netscape HTTP Cookie File\n\
+        .leetcode.com\tTRUE\t/\tTRUE\t2000\tcsrftoken\tcsrf-value\n\
+        #HttpOnly_.leetcode.com\tTRUE\t/\tTRUE\t0\tLEETCODE_SESSION\tsession-value\n";
+    let cookies = parse_netscape_cookies(content);
+    let session = Session::from_exported_cookies(&cookies, 1000).unwrap();
+
+    assert_eq!(session.id, "session-value");
+    assert_eq!(session.csrf, "csrf-value");
+    assert!(matches!(
+        Session::from_exported_cookies(&cookies, 3000),
+        Err(LeetUpError::SessionExpired)
+    ));
+}
+
+#[test]
+fn test_json_cookie_file() {
+    let content = r#"[
+        {"name": "csrftoken", "value": "csrf-value", "expirationDate": 2000.5},
+        {"name": "LEETCODE_SESSION", "value": "session-value"}
+    ]"#;
+    let cookies = parse_json_cookies(content).unwrap();
+    let session = Session::from_exported_cookies(&cookies, 1000).unwrap();
+
+    assert_eq!(session.id, "session-value");
+    assert_eq!(session.csrf, "csrf-value");
+    assert!(Session::from_exported_cookies(&cookies[..1], 1000).is_err());
+}
+
+#[test]
+fn test_session_claims() {
+    let payload = URL_SAFE_NO_PAD.encode(r#"{"username":"alice","expired_time_":1700000000}"#);
+    let session = Session::new(format!("header.{}.signature", payload), "csrf".into());
+    assert_eq!(
+        session.claims(),
+        Some(SessionClaims {
+            username: Some("alice".into()),
+            expires: Some(1_700_000_000)
+        })
+    );
+    assert_eq!(Session::new("opaque".into(), "csrf".into()).claims(), None);
+    assert_eq!(Session::new("a.!!.c".into(), "csrf".into()).claims(), None);
+}
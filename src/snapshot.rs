@@ -0,0 +1,86 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use tempfile::NamedTempFile;
+
+use crate::{cmd::create_config_directory, Result};
+
+/// Directory of the leetup directory holding the problem list snapshots.
+pub const SNAPSHOT_DIR: &str = "problems";
+
+/// Problem list of one site and user, kept as a JSON file of its own.
+///
+/// The file is replaced through a temporary file renamed over it, so a run
+/// reading it while another one refreshes it never sees a partial list.
+pub struct Snapshot {
+    path: PathBuf,
+}
+
+impl Snapshot {
+    /// Snapshot of the cache namespace `namespace`, see `cache_namespace`.
+    pub fn open(namespace: &str) -> Result<Self> {
+        Ok(Snapshot::in_dir(&create_config_directory()?, namespace))
+    }
+
+    fn in_dir(dir: &Path, namespace: &str) -> Self {
+        Snapshot {
+            path: dir.join(SNAPSHOT_DIR).join(format!("{}.json", namespace)),
+        }
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.is_file()
+    }
+
+    /// Content of the snapshot, if any.
+    pub fn read(&self) -> Result<Option<String>> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Atomically replace the snapshot with `content`.
+    pub fn write(&self, content: &str) -> Result<()> {
+        let dir = self.path.parent().expect("snapshot path has a parent");
+        fs::create_dir_all(dir)?;
+        // Created next to the snapshot, a rename never crosses filesystems.
+        let mut file = NamedTempFile::new_in(dir)?;
+        file.write_all(content.as_bytes())?;
+        file.persist(&self.path).map_err(|e| e.error)?;
+
+        Ok(())
+    }
+
+    pub fn remove(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn test_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    let snapshot = Snapshot::in_dir(dir.path(), "leetcode.com/anonymous");
+    assert!(!snapshot.exists());
+    assert_eq!(snapshot.read().unwrap(), None);
+    snapshot.remove().unwrap();
+
+    snapshot.write(r#"{"num_total":1}"#).unwrap();
+    snapshot.write(r#"{"num_total":2}"#).unwrap();
+    assert!(dir
+        .path()
+        .join("problems/leetcode.com/anonymous.json")
+        .is_file());
+    assert_eq!(snapshot.read().unwrap().unwrap(), r#"{"num_total":2}"#);
+    // Nothing but the snapshot is left behind
+    let files = fs::read_dir(dir.path().join("problems/leetcode.com")).unwrap();
+    assert_eq!(files.count(), 1);
+
+    snapshot.remove().unwrap();
+    assert!(!snapshot.exists());
+}
@@ -1,3 +1,18 @@
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::anyhow;
+use regex::{Captures, Regex};
+
+use crate::{model::Problem, LeetUpError, Result};
+
+/// Name of generated files unless `filename_template` is configured.
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{slug}.{ext}";
+
+/// Placeholders accepted by `filename_template`.
+pub const FILENAME_FIELDS: &[&str] = &["id", "slug", "difficulty", "lang", "ext"];
+
 #[derive(Copy, Clone)]
 pub enum Pattern {
     LeetUpInfo,
@@ -38,16 +53,43 @@ impl From<Pattern> for String {
     }
 }
 
-impl<'a> From<&'a Pattern> for String {
+impl From<&Pattern> for String {
     fn from(p: &Pattern) -> Self {
         String::from(*p)
     }
 }
 
-impl ToString for Pattern {
-    fn to_string(&self) -> String {
-        String::from(*self)
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", String::from(*self))
+    }
+}
+
+/// Code between the two `@leetup=code` markers of a solution file.
+///
+/// Unlike `parse_code`, which falls back to the whole input, this requires
+/// exactly one start and one end marker around some non-blank code.
+pub fn extract_marked_code(code: &str) -> Option<String> {
+    let code_pattern: String = Pattern::Code.into();
+    if code.matches(&code_pattern).count() != 2 {
+        return None;
+    }
+
+    parse_code(code).filter(|code| !code.trim().is_empty())
+}
+
+/// Replace the code between the `@leetup=code` markers of `content` with
+/// `code`, as returned by `extract_marked_code`.
+pub fn replace_marked_code(content: &str, code: &str) -> Option<String> {
+    let code_pattern: String = Pattern::Code.into();
+    let start = content.find(&code_pattern)? + code_pattern.len();
+    let end_marker = start + content[start..].find(&code_pattern)?;
+    let end = content[..end_marker].rfind('\n')? + 1;
+    if end < start {
+        return None;
     }
+
+    Some(format!("{}{}{}", &content[..start], code, &content[end..]))
 }
 
 /// Parse code to submit only the relevant chunk of code.
@@ -68,7 +110,8 @@ pub fn parse_code(code: &str) -> Option<String> {
     let end_index = match code.find(&code_pattern) {
         Some(index) => {
             let code = &code[..index];
-            let index = code.rfind('\n').unwrap();
+            // Both markers on one line leave no code between them.
+            let index = code.rfind('\n')?;
             index + 1
         }
         None => code.len(),
@@ -78,6 +121,151 @@ pub fn parse_code(code: &str) -> Option<String> {
     Some(code.into())
 }
 
+/// `{field}` or `{field:0N}` to pad the value with zeros to N characters.
+fn filename_placeholder_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"\{(\w+)(?::0(\d+))?\}").expect("valid filename placeholder regex")
+    })
+}
+
+/// Check that `template` only uses known fields and stays inside the
+/// directory files are generated in.
+pub fn check_filename_template(template: &str) -> Result<()> {
+    for caps in filename_placeholder_regex().captures_iter(template) {
+        let field = &caps[1];
+        if !FILENAME_FIELDS.contains(&field) {
+            return Err(LeetUpError::Any(anyhow!(
+                "Unknown filename_template field `{{{}}}`, expected one of: {}",
+                field,
+                FILENAME_FIELDS.join(", ")
+            )));
+        }
+    }
+    let is_relative = Path::new(template)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !is_relative {
+        return Err(LeetUpError::Any(anyhow!(
+            "filename_template `{}` must be a relative path without `..`",
+            template
+        )));
+    }
+
+    Ok(())
+}
+
+/// Relative path of the file generated for `problem`, e.g.
+/// `{difficulty}/{id:04}-{slug}.{ext}` gives `Easy/0001-two-sum.rs`.
+pub fn render_filename(template: &str, problem: &Problem, ext: &str) -> Result<PathBuf> {
+    check_filename_template(template)?;
+    let rendered = filename_placeholder_regex().replace_all(template, |caps: &Captures| {
+        let value = match &caps[1] {
+            "id" => problem.id.to_string(),
+            "slug" => problem.slug.to_owned(),
+            "difficulty" => problem.difficulty.as_str().to_string(),
+            "lang" => problem.lang.to_owned(),
+            "ext" => ext.to_string(),
+            _ => unreachable!("checked by check_filename_template"),
+        };
+        let width = caps
+            .get(2)
+            .map_or(0, |width| width.as_str().parse().unwrap_or(0));
+        format!("{:0>width$}", value, width = width)
+    });
+
+    Ok(PathBuf::from(rendered.into_owned()))
+}
+
+#[test]
+fn test_render_filename() {
+    use crate::model::DifficultyType;
+
+    let problem = Problem {
+        id: 1,
+        slug: "two-sum".into(),
+        lang: "rust".into(),
+        link: String::new(),
+        typed_code: None,
+        difficulty: DifficultyType::Easy,
+    };
+    let render = |template| render_filename(template, &problem, "rs").unwrap();
+    assert_eq!(
+        render(DEFAULT_FILENAME_TEMPLATE),
+        PathBuf::from("two-sum.rs")
+    );
+    assert_eq!(
+        render("{id:04}-{slug}.{ext}"),
+        PathBuf::from("0001-two-sum.rs")
+    );
+    assert_eq!(
+        render("{difficulty}/{lang}/{id}-{slug}.{ext}"),
+        PathBuf::from("Easy/rust/1-two-sum.rs")
+    );
+
+    assert!(check_filename_template("{title}.{ext}")
+        .unwrap_err()
+        .to_string()
+        .contains("`{title}`"));
+    assert!(check_filename_template("../{slug}.{ext}").is_err());
+    assert!(check_filename_template("/tmp/{slug}.{ext}").is_err());
+}
+
+#[test]
+fn test_replace_marked_code() {
+    let old = "// @leetup=info id=1\n// @leetup=code\nfn mine() {}\n// @leetup=code\n";
+    let new = "// @leetup=info id=1 lang=rust\n// @leetup=code\n\nfn stub() {}\n// @leetup=code\nfn main() {}\n";
+
+    let merged = replace_marked_code(new, &extract_marked_code(old).unwrap()).unwrap();
+    assert_eq!(
+        merged,
+        "// @leetup=info id=1 lang=rust\n// @leetup=code\nfn mine() {}\n// @leetup=code\nfn main() {}\n"
+    );
+    assert_eq!(
+        replace_marked_code("fn stub() {}\n", "fn mine() {}\n"),
+        None
+    );
+}
+
+#[test]
+fn test_extract_marked_code() {
+    let marked = |body: &str| format!("// @leetup=info id=1\n{}", body);
+
+    assert_eq!(
+        extract_marked_code(&marked("// @leetup=code\nfn f() {}\n// @leetup=code\n")),
+        Some("\nfn f() {}\n".to_string())
+    );
+    // Missing end or both markers
+    assert_eq!(
+        extract_marked_code(&marked("// @leetup=code\nfn f() {}\n")),
+        None
+    );
+    assert_eq!(extract_marked_code(&marked("fn f() {}\n")), None);
+    // Both markers on the same line
+    assert_eq!(
+        extract_marked_code(&marked("// @leetup=code x // @leetup=code\n")),
+        None
+    );
+    // Nested or multiple regions are ambiguous
+    assert_eq!(
+        extract_marked_code(&marked(
+            "// @leetup=code\n// @leetup=code\nfn f() {}\n// @leetup=code\n"
+        )),
+        None
+    );
+    assert_eq!(
+        extract_marked_code(&marked(
+            "// @leetup=code\nfn f() {}\n// @leetup=code\n// @leetup=code\nfn g() {}\n// @leetup=code\n"
+        )),
+        None
+    );
+    // Nothing to submit
+    assert_eq!(
+        extract_marked_code(&marked("// @leetup=code\n  \n// @leetup=code\n")),
+        None
+    );
+}
+
 #[test]
 fn test_parse_with_comments() {
     let code = r#"
@@ -19,6 +19,189 @@ mod tests {
             .stdout(contains(env!("CARGO_PKG_VERSION")));
     }
 
+    #[test]
+    fn json_errors() {
+        let home = tempfile::tempdir().unwrap();
+        Command::cargo_bin("leetup")
+            .unwrap()
+            .env("HOME", home.path())
+            .args(["--json-errors", "--offline", "user", "-l"])
+            .assert()
+            .code(3)
+            .stderr(contains(r#""error_type":"offline""#))
+            .stderr(contains(r#""http_status":null"#));
+    }
+
+    #[test]
+    fn session_env_requires_both_variables() {
+        let home = tempfile::tempdir().unwrap();
+        Command::cargo_bin("leetup")
+            .unwrap()
+            .env("HOME", home.path())
+            .env("LEETCODE_SESSION", "session")
+            .env_remove("LEETCODE_CSRF")
+            .args(["--offline", "history"])
+            .assert()
+            .failure()
+            .stderr(contains("`LEETCODE_CSRF` is not"));
+    }
+
+    #[test]
+    fn result_requires_login() {
+        let home = tempfile::tempdir().unwrap();
+        Command::cargo_bin("leetup")
+            .unwrap()
+            .env("HOME", home.path())
+            .env_remove("LEETCODE_SESSION")
+            .env_remove("LEETCODE_CSRF")
+            .args(["result", "1234"])
+            .assert()
+            .code(4);
+        Command::cargo_bin("leetup")
+            .unwrap()
+            .env("HOME", home.path())
+            .args(["result", "runcode_1"])
+            .assert()
+            .failure()
+            .stderr(contains("invalid digit"));
+    }
+
+    #[test]
+    fn submit_last_pick() {
+        let home = tempfile::tempdir().unwrap();
+        let submit = || {
+            Command::cargo_bin("leetup")
+                .unwrap()
+                .env("HOME", home.path())
+                .args(["submit", "--dry-run"])
+                .assert()
+        };
+        submit().failure().stderr(contains("No problem picked yet"));
+
+        let file = home.path().join("two-sum.rs");
+        std::fs::write(
+            &file,
+            "// @leetup=info id=1 lang=rust slug=two-sum\n// @leetup=code\nfn main() {}\n// @leetup=code\n",
+        )
+        .unwrap();
+        std::fs::write(
+            home.path().join(".leetup/last_pick.json"),
+            serde_json::json!({ "id": 1, "slug": "two-sum", "path": file }).to_string(),
+        )
+        .unwrap();
+        submit()
+            .success()
+            .stdout(contains("Submitting"))
+            .stdout(contains("fn main() {}"));
+    }
+
+    #[test]
+    fn whoami() {
+        let home = tempfile::tempdir().unwrap();
+        Command::cargo_bin("leetup")
+            .unwrap()
+            .env("HOME", home.path())
+            .env_remove("LEETCODE_SESSION")
+            .env_remove("LEETCODE_CSRF")
+            .args(["--offline", "whoami"])
+            .assert()
+            .code(4)
+            .stdout(contains("Not logged in to leetcode.com"));
+        Command::cargo_bin("leetup")
+            .unwrap()
+            .env("HOME", home.path())
+            .env(
+                "LEETCODE_SESSION",
+                "header.eyJ1c2VybmFtZSI6ImFsaWNlIn0.signature",
+            )
+            .env("LEETCODE_CSRF", "csrf")
+            .args(["--offline", "whoami"])
+            .assert()
+            .success()
+            .stdout(contains("alice"))
+            .stdout(contains("Expires  unknown"));
+    }
+
+    #[test]
+    fn submit_lang_override() {
+        let home = tempfile::tempdir().unwrap();
+        let file = home.path().join("1.two-sum.rs");
+        std::fs::write(
+            &file,
+            "// @leetup=info id=1 lang=rust slug=two-sum\n// @leetup=code\nint x;\n// @leetup=code\n",
+        )
+        .unwrap();
+        let submit = |force: bool| {
+            let mut cmd = Command::cargo_bin("leetup").unwrap();
+            cmd.env("HOME", home.path())
+                .args(["submit", "--dry-run", "--lang", "cpp"])
+                .arg(&file);
+            if force {
+                cmd.arg("--force");
+            }
+            cmd.assert()
+        };
+        submit(false)
+            .code(2)
+            .stderr(contains("use --force to submit it anyway"));
+        submit(true)
+            .success()
+            .stdout(contains("Language: cpp"))
+            .stderr(contains("Warning"));
+    }
+
+    #[test]
+    fn ambiguous_keyword_without_terminal() {
+        let home = tempfile::tempdir().unwrap();
+        let snapshot = home.path().join(".leetup/problems/leetcode.com");
+        std::fs::create_dir_all(&snapshot).unwrap();
+        let pair = |id: usize, title: &str, slug: &str| {
+            serde_json::json!({
+                "stat": {
+                    "question_id": id,
+                    "question__title": title,
+                    "question__title_slug": slug,
+                    "question__hide": false,
+                    "total_acs": 1,
+                    "total_submitted": 2,
+                    "frontend_question_id": id,
+                    "is_new_question": false
+                },
+                "status": null,
+                "difficulty": {"level": 1},
+                "paid_only": false,
+                "is_favor": false,
+                "frequency": 0,
+                "progress": 0
+            })
+        };
+        let problems = serde_json::json!({
+            "user_name": "",
+            "num_solved": 0,
+            "num_total": 2,
+            "ac_easy": 0,
+            "ac_medium": 0,
+            "ac_hard": 0,
+            "stat_status_pairs": [
+                pair(1, "Two Sum", "two-sum"),
+                pair(167, "Two Sum II", "two-sum-ii"),
+            ]
+        });
+        std::fs::write(snapshot.join("anonymous.json"), problems.to_string()).unwrap();
+
+        Command::cargo_bin("leetup")
+            .unwrap()
+            .env("HOME", home.path())
+            .env_remove("LEETCODE_SESSION")
+            .env_remove("LEETCODE_CSRF")
+            .args(["--offline", "show", "two"])
+            .stdin(std::process::Stdio::null())
+            .assert()
+            .failure()
+            .stderr(contains("`two` matches several problems"))
+            .stderr(contains("167 two-sum-ii"));
+    }
+
     fn _get_id(problem: &str) -> usize {
         println!("{}", problem);
         let start_index = problem.find(" [").unwrap();
@@ -47,7 +230,7 @@ mod tests {
         // Test OrderBy works by check first and last id
         //
         // NOTE: For some reason, result.last() is empty!
-        assert_eq!(1, _get_id(result.get(0).as_ref().unwrap()));
+        assert_eq!(1, _get_id(result.first().as_ref().unwrap()));
         assert_eq!(n, _get_id(result.get(n - 1).as_ref().unwrap()));
     }
 